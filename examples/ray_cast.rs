@@ -10,7 +10,7 @@ use rand::prelude::*;
 
 use bevy_liquidfun::dynamics::{
     b2BodyBundle, b2CategoryFilter, b2Filter, b2Fixture, b2FixtureDef, b2RayCastAll, b2RayCastAny,
-    b2RayCastClosest,
+    b2RayCastClosest, PhysicsQuery,
 };
 use bevy_liquidfun::plugins::{LiquidFunDebugDrawPlugin, LiquidFunPlugin};
 use bevy_liquidfun::utils::DebugDrawFixtures;
@@ -231,7 +231,7 @@ fn check_delete_body_key(
 fn cast_ray(
     mut gizmos: Gizmos,
     time: Res<Time>,
-    mut b2_world: NonSendMut<b2World>,
+    physics_query: PhysicsQuery,
     mode: Res<RayCastMode>,
 ) {
     let ray_start = Vec2::new(0., 10.);
@@ -244,21 +244,21 @@ fn cast_ray(
     match mode.as_ref() {
         RayCastMode::Closest => {
             let callback = b2RayCastClosest::new();
-            let hit = b2_world.ray_cast_with_filter(callback, filter, &ray_start, &ray_end);
+            let hit = physics_query.ray_cast_with_filter(callback, filter, &ray_start, &ray_end);
             if let Some(hit) = hit {
                 gizmos.line_2d(hit.point, hit.point + hit.normal, Color::ORANGE_RED);
             }
         }
         RayCastMode::Any => {
             let callback = b2RayCastAny::new();
-            let hit = b2_world.ray_cast_with_filter(callback, filter, &ray_start, &ray_end);
+            let hit = physics_query.ray_cast_with_filter(callback, filter, &ray_start, &ray_end);
             if let Some(hit) = hit {
                 gizmos.line_2d(hit.point, hit.point + hit.normal, Color::ORANGE_RED);
             }
         }
         RayCastMode::All => {
             let callback = b2RayCastAll::new();
-            let hits = b2_world.ray_cast_with_filter(callback, filter, &ray_start, &ray_end);
+            let hits = physics_query.ray_cast_with_filter(callback, filter, &ray_start, &ray_end);
             for hit in hits {
                 gizmos.line_2d(hit.point, hit.point + hit.normal, Color::ORANGE_RED);
             }
@@ -1,3 +1,4 @@
+use autocxx::prelude::UniquePtr;
 use autocxx::WithinUniquePtr;
 use bevy::prelude::*;
 use libliquidfun_sys::box2d::ffi::b2Vec2;
@@ -7,7 +8,7 @@ use std::f32::consts::PI;
 use crate::internal::*;
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum b2Shape {
     Circle {
         radius: f32,
@@ -67,6 +68,12 @@ impl b2Shape {
         b2Shape::Polygon { vertices }
     }
 
+    /// Like [`Self::to_ffi`], but the returned shape is freed when dropped.
+    pub(crate) fn to_ffi_owned(&self) -> UniquePtr<ffi::b2Shape> {
+        let ffi_shape = self.to_ffi() as *const ffi::b2Shape as *mut ffi::b2Shape;
+        unsafe { UniquePtr::from_raw(ffi_shape) }
+    }
+
     pub(crate) fn to_ffi<'a>(&self) -> &'a ffi::b2Shape {
         match self {
             b2Shape::Circle { radius, position } => circle_to_ffi(*radius, *position),
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+/// A static bounding volume hierarchy over axis-aligned boxes, used to answer region queries
+/// without testing every item. The tree is built once from all items and is rebuilt from scratch
+/// when they change.
+#[derive(Debug)]
+pub(crate) struct AabbTree<T: Copy> {
+    nodes: Vec<AabbTreeNode<T>>,
+}
+
+#[derive(Debug)]
+struct AabbTreeNode<T: Copy> {
+    min: Vec2,
+    max: Vec2,
+    kind: AabbTreeNodeKind<T>,
+}
+
+#[derive(Debug)]
+enum AabbTreeNodeKind<T: Copy> {
+    Leaf(T),
+    Branch(usize, usize),
+}
+
+impl<T: Copy> AabbTree<T> {
+    /// Builds the tree by recursively splitting the items at the median of their centers along
+    /// the longest axis of the current node.
+    pub fn new(mut items: Vec<(Vec2, Vec2, T)>) -> Self {
+        let mut nodes = Vec::with_capacity(items.len() * 2);
+        if !items.is_empty() {
+            Self::build_node(&mut nodes, &mut items);
+        }
+        Self { nodes }
+    }
+
+    fn build_node(nodes: &mut Vec<AabbTreeNode<T>>, items: &mut [(Vec2, Vec2, T)]) -> usize {
+        let (min, max) = items.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), (item_min, item_max, _)| (min.min(*item_min), max.max(*item_max)),
+        );
+
+        let index = nodes.len();
+        if items.len() == 1 {
+            nodes.push(AabbTreeNode {
+                min,
+                max,
+                kind: AabbTreeNodeKind::Leaf(items[0].2),
+            });
+            return index;
+        }
+
+        // Placeholder, replaced once both children have been built
+        nodes.push(AabbTreeNode {
+            min,
+            max,
+            kind: AabbTreeNodeKind::Branch(0, 0),
+        });
+
+        let extents = max - min;
+        let axis = if extents.x >= extents.y { 0 } else { 1 };
+        let median = items.len() / 2;
+        items.select_nth_unstable_by(median, |(a_min, a_max, _), (b_min, b_max, _)| {
+            let a_center = (a_min[axis] + a_max[axis]) * 0.5;
+            let b_center = (b_min[axis] + b_max[axis]) * 0.5;
+            a_center.total_cmp(&b_center)
+        });
+
+        let (left_items, right_items) = items.split_at_mut(median);
+        let left = Self::build_node(nodes, left_items);
+        let right = Self::build_node(nodes, right_items);
+        nodes[index].kind = AabbTreeNodeKind::Branch(left, right);
+        index
+    }
+
+    /// Calls `visit` for every item whose box overlaps the given box. `visit` returns false to
+    /// terminate the query, in which case this returns false as well.
+    pub fn query(&self, min: Vec2, max: Vec2, mut visit: impl FnMut(T) -> bool) -> bool {
+        if self.nodes.is_empty() {
+            return true;
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.min.cmpgt(max).any() || node.max.cmplt(min).any() {
+                continue;
+            }
+            match node.kind {
+                AabbTreeNodeKind::Leaf(item) => {
+                    if !visit(item) {
+                        return false;
+                    }
+                }
+                AabbTreeNodeKind::Branch(left, right) => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_all(tree: &AabbTree<usize>, min: Vec2, max: Vec2) -> Vec<usize> {
+        let mut result = Vec::new();
+        tree.query(min, max, |item| {
+            result.push(item);
+            true
+        });
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn finds_exactly_the_overlapping_items() {
+        let items: Vec<_> = (0..100)
+            .map(|i| {
+                let min = Vec2::new((i % 10) as f32, (i / 10) as f32);
+                (min, min + Vec2::splat(0.5), i)
+            })
+            .collect();
+        let tree = AabbTree::new(items);
+
+        assert_eq!(
+            query_all(&tree, Vec2::new(2.25, 3.25), Vec2::new(3.75, 4.)),
+            vec![32, 33, 42, 43]
+        );
+        assert_eq!(
+            query_all(&tree, Vec2::new(0.6, 0.6), Vec2::new(0.9, 0.9)),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            query_all(&tree, Vec2::splat(-100.), Vec2::splat(100.)),
+            (0..100).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn stops_when_visit_returns_false() {
+        let items = (0..10)
+            .map(|i| (Vec2::ZERO, Vec2::ONE, i))
+            .collect::<Vec<_>>();
+        let tree = AabbTree::new(items);
+
+        let mut visited = 0;
+        let completed = tree.query(Vec2::ZERO, Vec2::ONE, |_| {
+            visited += 1;
+            visited < 3
+        });
+        assert!(!completed);
+        assert_eq!(visited, 3);
+    }
+
+    #[test]
+    fn empty_tree_reports_nothing() {
+        let tree = AabbTree::<usize>::new(Vec::new());
+        assert!(query_all(&tree, Vec2::splat(-1.), Vec2::ONE).is_empty());
+    }
+}
@@ -8,7 +8,7 @@ use crate::dynamics::b2World;
 use crate::internal::{to_Vec2, to_b2Vec2};
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 pub enum b2BodyType {
     #[default]
     Static,
@@ -40,6 +40,7 @@ impl Into<ffi::b2BodyType> for b2BodyType {
 #[derive(Component, Debug)]
 pub struct b2Body {
     pub(crate) fixtures: HashSet<Entity>,
+    pub(crate) joints: HashSet<Entity>,
 
     pub body_type: b2BodyType,
     pub position: Vec2,
@@ -50,6 +51,13 @@ pub struct b2Body {
     pub allow_sleep: bool,
     pub fixed_rotation: bool,
 
+    /// Treat this body as a fast moving object that should not tunnel through other dynamic
+    /// bodies.
+    pub bullet: bool,
+
+    /// A disabled body does not collide and is not simulated.
+    pub enabled: bool,
+
     mass: f32,
 }
 
@@ -57,6 +65,7 @@ impl b2Body {
     pub fn new(body_def: &b2BodyDef) -> Self {
         b2Body {
             fixtures: HashSet::new(),
+            joints: HashSet::new(),
             body_type: body_def.body_type,
             position: body_def.position,
             angle: body_def.angle,
@@ -66,6 +75,8 @@ impl b2Body {
             awake: true,
             allow_sleep: body_def.allow_sleep,
             fixed_rotation: body_def.fixed_rotation,
+            bullet: body_def.bullet,
+            enabled: body_def.enabled,
         }
     }
 
@@ -81,6 +92,23 @@ impl b2Body {
 
     pub fn sync_to_world(&self, entity: Entity, world: &mut b2World) {
         let body_ptr = world.get_body_ptr_mut(entity).unwrap();
+
+        // changing these is expensive or has side effects like resetting the mass data, only
+        // apply them when they differ
+        let body_type: ffi::b2BodyType = self.body_type.into();
+        if body_ptr.as_ref().GetType() != body_type {
+            body_ptr.as_mut().SetType(body_type);
+        }
+        if body_ptr.as_ref().IsFixedRotation() != self.fixed_rotation {
+            body_ptr.as_mut().SetFixedRotation(self.fixed_rotation);
+        }
+        if body_ptr.as_ref().IsBullet() != self.bullet {
+            body_ptr.as_mut().SetBullet(self.bullet);
+        }
+        if body_ptr.as_ref().IsEnabled() != self.enabled {
+            body_ptr.as_mut().SetEnabled(self.enabled);
+        }
+
         body_ptr
             .as_mut()
             .SetTransform(&to_b2Vec2(&self.position), self.angle);
@@ -99,16 +127,36 @@ impl b2Body {
     pub fn fixtures(&self) -> &HashSet<Entity> {
         &self.fixtures
     }
+
+    pub fn joints(&self) -> &HashSet<Entity> {
+        &self.joints
+    }
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct b2BodyDef {
     pub body_type: b2BodyType,
     pub position: Vec2,
     pub angle: f32,
     pub allow_sleep: bool,
     pub fixed_rotation: bool,
+    pub bullet: bool,
+    pub enabled: bool,
+}
+
+impl Default for b2BodyDef {
+    fn default() -> Self {
+        Self {
+            body_type: b2BodyType::default(),
+            position: Vec2::ZERO,
+            angle: 0.,
+            allow_sleep: false,
+            fixed_rotation: false,
+            bullet: false,
+            enabled: true,
+        }
+    }
 }
 
 #[allow(non_camel_case_types)]
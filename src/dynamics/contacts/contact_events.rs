@@ -1,6 +1,6 @@
-use bevy::prelude::Event;
+use bevy::prelude::{Entity, Event};
 
-use crate::dynamics::b2Contact;
+use crate::dynamics::{b2Contact, b2ContactImpulse};
 
 #[allow(non_camel_case_types)]
 #[derive(Event, Debug, Copy, Clone)]
@@ -9,3 +9,69 @@ pub struct b2BeginContactEvent(pub b2Contact);
 #[allow(non_camel_case_types)]
 #[derive(Event, Debug, Copy, Clone)]
 pub struct b2EndContactEvent(pub b2Contact);
+
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ContactImpulseEvent(pub b2ContactImpulse);
+
+/// Sent when a particle starts touching a fixture. Only reported for particles with the
+/// `FixtureContactListenerParticle` flag.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ParticleBodyContactBegin {
+    pub particle_system: Entity,
+    pub particle_index: i32,
+    pub fixture: Entity,
+    pub body: Entity,
+}
+
+/// Sent when a particle stops touching a fixture. Only reported for particles with the
+/// `FixtureContactListenerParticle` flag.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ParticleBodyContactEnd {
+    pub particle_system: Entity,
+    pub particle_index: i32,
+    pub fixture: Entity,
+    pub body: Entity,
+}
+
+/// Sent when two particles start touching. Only reported for particles with the
+/// `ParticleContactListenerParticle` flag.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ParticleParticleContactBegin {
+    pub particle_system: Entity,
+    pub particle_index_a: i32,
+    pub particle_index_b: i32,
+}
+
+/// Sent when two particles stop touching. Only reported for particles with the
+/// `ParticleContactListenerParticle` flag.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2ParticleParticleContactEnd {
+    pub particle_system: Entity,
+    pub particle_index_a: i32,
+    pub particle_index_b: i32,
+}
+
+/// Sent when a fixture starts overlapping a sensor fixture that has a `b2SensorOverlaps`
+/// component.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2SensorEnterEvent {
+    pub sensor: Entity,
+    pub fixture: Entity,
+    pub body: Entity,
+}
+
+/// Sent when a fixture stops overlapping a sensor fixture that has a `b2SensorOverlaps`
+/// component.
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2SensorExitEvent {
+    pub sensor: Entity,
+    pub fixture: Entity,
+    pub body: Entity,
+}
@@ -1,13 +1,20 @@
 use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
 
 use bevy::prelude::Entity;
 
 use libliquidfun_sys::box2d::ffi::{
-    b2Contact as ffi_b2Contact, b2ContactImpulse, b2ContactListenerImpl, b2Fixture, b2Manifold,
-    b2ParticleBodyContact as ffi_b2ParticleBodyContact, b2ParticleContact, b2ParticleSystem,
+    b2Contact as ffi_b2Contact, b2ContactImpulse as ffi_b2ContactImpulse, b2ContactListenerImpl,
+    b2Fixture, b2Manifold, b2ParticleBodyContact as ffi_b2ParticleBodyContact,
+    b2ParticleContact as ffi_b2ParticleContact, b2ParticleSystem,
 };
 
-use crate::dynamics::b2Contact;
+use crate::dynamics::{
+    b2Contact, b2ContactImpulse, b2ParticleBodyContact, b2ParticleBodyContactBegin,
+    b2ParticleBodyContactEnd, b2ParticleParticleContactBegin, b2ParticleParticleContactEnd,
+    b2PreSolveContact, b2PreSolveHook, max_normal_impulse, OneWayPlatform, ParticleSystemEntities,
+};
+use crate::internal::{fixture_entities, read_contact_impulse};
 
 #[allow(non_camel_case_types)]
 #[derive(Debug)]
@@ -15,14 +22,38 @@ pub struct b2ContactListener {
     fixture_contacts: HashMap<(Entity, Entity), b2Contact>,
     begun_fixture_contacts: HashSet<(Entity, Entity)>,
     ended_fixture_contacts: HashMap<(Entity, Entity), b2Contact>,
+    recreated_fixtures: HashSet<Entity>,
+    suspended_fixture_contacts: HashMap<(Entity, Entity), b2Contact>,
+    contact_impulses: HashMap<(Entity, Entity), b2ContactImpulse>,
+    contact_impulse_thresholds: HashMap<Entity, f32>,
+    bodies_with_contacts: HashSet<Entity>,
+    pre_solve_hooks: Vec<Box<dyn b2PreSolveHook>>,
+    one_way_platforms: HashMap<Entity, OneWayPlatform>,
+    particle_systems: ParticleSystemEntities,
+    begun_particle_body_contacts: Vec<b2ParticleBodyContactBegin>,
+    ended_particle_body_contacts: Vec<b2ParticleBodyContactEnd>,
+    begun_particle_particle_contacts: Vec<b2ParticleParticleContactBegin>,
+    ended_particle_particle_contacts: Vec<b2ParticleParticleContactEnd>,
 }
 
 impl b2ContactListener {
-    pub fn new() -> Self {
+    pub fn new(particle_systems: ParticleSystemEntities) -> Self {
         Self {
             fixture_contacts: Default::default(),
             begun_fixture_contacts: Default::default(),
             ended_fixture_contacts: Default::default(),
+            recreated_fixtures: Default::default(),
+            suspended_fixture_contacts: Default::default(),
+            contact_impulses: Default::default(),
+            contact_impulse_thresholds: Default::default(),
+            bodies_with_contacts: Default::default(),
+            pre_solve_hooks: Default::default(),
+            one_way_platforms: Default::default(),
+            particle_systems,
+            begun_particle_body_contacts: Default::default(),
+            ended_particle_body_contacts: Default::default(),
+            begun_particle_particle_contacts: Default::default(),
+            ended_particle_particle_contacts: Default::default(),
         }
     }
 
@@ -38,9 +69,80 @@ impl b2ContactListener {
         &self.ended_fixture_contacts
     }
 
+    /// The strongest impulse of each contact that was solved since the last call to
+    /// [`Self::clear_contact_changes`]. Only contacts of fixtures with a contact impulse
+    /// threshold that was reached are recorded.
+    pub fn contact_impulses(&self) -> &HashMap<(Entity, Entity), b2ContactImpulse> {
+        &self.contact_impulses
+    }
+
+    pub(crate) fn contact_impulse_thresholds_mut(&mut self) -> &mut HashMap<Entity, f32> {
+        &mut self.contact_impulse_thresholds
+    }
+
+    /// The bodies with a `b2Contacts` component, whose contacts are updated on every step.
+    pub(crate) fn bodies_with_contacts_mut(&mut self) -> &mut HashSet<Entity> {
+        &mut self.bodies_with_contacts
+    }
+
+    pub fn begun_particle_body_contacts(&self) -> &Vec<b2ParticleBodyContactBegin> {
+        &self.begun_particle_body_contacts
+    }
+
+    pub fn ended_particle_body_contacts(&self) -> &Vec<b2ParticleBodyContactEnd> {
+        &self.ended_particle_body_contacts
+    }
+
+    pub fn begun_particle_particle_contacts(&self) -> &Vec<b2ParticleParticleContactBegin> {
+        &self.begun_particle_particle_contacts
+    }
+
+    pub fn ended_particle_particle_contacts(&self) -> &Vec<b2ParticleParticleContactEnd> {
+        &self.ended_particle_particle_contacts
+    }
+
+    pub(crate) fn add_pre_solve_hook(&mut self, hook: Box<dyn b2PreSolveHook>) {
+        self.pre_solve_hooks.push(hook);
+    }
+
+    pub(crate) fn one_way_platforms_mut(&mut self) -> &mut HashMap<Entity, OneWayPlatform> {
+        &mut self.one_way_platforms
+    }
+
+    /// Contacts of the given fixture that end until [`Self::end_fixture_recreation`] is called
+    /// are suspended instead of reported as ended. Suspended contacts stay in the fixture
+    /// contacts until the next step, a suspended contact that begins again during that step is
+    /// restored without a begin event.
+    pub(crate) fn begin_fixture_recreation(&mut self, fixture: Entity) {
+        self.recreated_fixtures.insert(fixture);
+    }
+
+    /// Returns the bodies touching the recreated fixture before it was destroyed.
+    pub(crate) fn end_fixture_recreation(&mut self, fixture: Entity) -> Vec<Entity> {
+        self.recreated_fixtures.remove(&fixture);
+        self.suspended_fixture_contacts
+            .values()
+            .filter(|contact| contact.fixture_a == fixture || contact.fixture_b == fixture)
+            .flat_map(|contact| [contact.body_a, contact.body_b])
+            .collect()
+    }
+
+    /// Reports the suspended contacts that did not begin again during the last step as ended.
+    pub(crate) fn end_suspended_fixture_contacts(&mut self) {
+        for (key, contact) in self.suspended_fixture_contacts.drain() {
+            self.fixture_contacts.remove(&key);
+            self.ended_fixture_contacts.insert(key, contact);
+        }
+    }
+
     pub fn clear_contact_changes(&mut self) {
         self.begun_fixture_contacts.clear();
         self.ended_fixture_contacts.clear();
+        self.contact_impulses.clear();
+        self.begun_particle_body_contacts.clear();
+        self.ended_particle_body_contacts.clear();
+        self.begun_particle_particle_contacts.clear();
+        self.ended_particle_particle_contacts.clear();
     }
 }
 
@@ -49,40 +151,179 @@ impl b2ContactListenerImpl for b2ContactListener {
         let contact = b2Contact::from_ffi_contact(contact);
         let key = contact.get_contact_key();
         self.fixture_contacts.insert(key, contact);
-        self.begun_fixture_contacts.insert(key);
+        if self.suspended_fixture_contacts.remove(&key).is_none() {
+            self.begun_fixture_contacts.insert(key);
+        }
     }
     fn end_contact(&mut self, contact: &mut ffi_b2Contact) {
         let contact = b2Contact::from_ffi_contact(contact);
         let key = contact.get_contact_key();
-        self.fixture_contacts.remove(&key);
-        self.ended_fixture_contacts.insert(key, contact);
+        if self.recreated_fixtures.contains(&contact.fixture_a)
+            || self.recreated_fixtures.contains(&contact.fixture_b)
+        {
+            // stays in fixture_contacts until the next step tells whether it still touches
+            self.suspended_fixture_contacts.insert(key, contact);
+        } else {
+            self.fixture_contacts.remove(&key);
+            self.ended_fixture_contacts.insert(key, contact);
+        }
     }
     fn begin_particle_body_contact(
         &mut self,
-        _particle_system: &mut b2ParticleSystem,
-        _contact: &mut ffi_b2ParticleBodyContact,
+        particle_system: &mut b2ParticleSystem,
+        contact: &mut ffi_b2ParticleBodyContact,
     ) {
+        let particle_system = self.particle_systems.get(particle_system);
+        if let None = particle_system {
+            return;
+        }
+        let contact = b2ParticleBodyContact::from_ffi_contact(contact);
+        self.begun_particle_body_contacts
+            .push(b2ParticleBodyContactBegin {
+                particle_system: particle_system.unwrap(),
+                particle_index: contact.particle_index,
+                fixture: contact.fixture,
+                body: contact.body,
+            });
     }
     fn end_particle_body_contact(
         &mut self,
-        _fixture: &mut b2Fixture,
-        _particle_system: &mut b2ParticleSystem,
-        _particle_index: i32,
+        fixture: &mut b2Fixture,
+        particle_system: &mut b2ParticleSystem,
+        particle_index: i32,
     ) {
+        let particle_system = self.particle_systems.get(particle_system);
+        if let None = particle_system {
+            return;
+        }
+        let (fixture_entity, body_entity) = fixture_entities(fixture);
+        self.ended_particle_body_contacts
+            .push(b2ParticleBodyContactEnd {
+                particle_system: particle_system.unwrap(),
+                particle_index,
+                fixture: fixture_entity,
+                body: body_entity,
+            });
     }
     fn begin_particle_particle_contact(
         &mut self,
-        _particle_system: &mut b2ParticleSystem,
-        _contact: &mut b2ParticleContact,
+        particle_system: &mut b2ParticleSystem,
+        contact: &mut ffi_b2ParticleContact,
     ) {
+        let particle_system = self.particle_systems.get(particle_system);
+        if let None = particle_system {
+            return;
+        }
+        self.begun_particle_particle_contacts
+            .push(b2ParticleParticleContactBegin {
+                particle_system: particle_system.unwrap(),
+                particle_index_a: i32::from(contact.GetIndexA()),
+                particle_index_b: i32::from(contact.GetIndexB()),
+            });
     }
     fn end_particle_particle_contact(
         &mut self,
-        _particle_system: &mut b2ParticleSystem,
-        _index_a: i32,
-        _index_b: i32,
+        particle_system: &mut b2ParticleSystem,
+        index_a: i32,
+        index_b: i32,
     ) {
+        let particle_system = self.particle_systems.get(particle_system);
+        if let None = particle_system {
+            return;
+        }
+        self.ended_particle_particle_contacts
+            .push(b2ParticleParticleContactEnd {
+                particle_system: particle_system.unwrap(),
+                particle_index_a: index_a,
+                particle_index_b: index_b,
+            });
+    }
+    fn pre_solve(&mut self, contact: &mut ffi_b2Contact, _old_manifold: &b2Manifold) {
+        if self.pre_solve_hooks.is_empty() {
+            // only gather the full contact data for contacts that involve a platform
+            let (fixture_a, fixture_b) = unsafe {
+                let mut contact = Pin::new_unchecked(&mut *contact);
+                (
+                    fixture_entities(contact.as_mut().GetFixtureA().as_mut().unwrap()).0,
+                    fixture_entities(contact.as_mut().GetFixtureB().as_mut().unwrap()).0,
+                )
+            };
+            if !self.one_way_platforms.contains_key(&fixture_a)
+                && !self.one_way_platforms.contains_key(&fixture_b)
+            {
+                return;
+            }
+        }
+
+        let mut pre_solve_contact = b2PreSolveContact::from_ffi_contact(contact);
+        if let Some(platform) = self.one_way_platforms.get(&pre_solve_contact.fixture_a) {
+            platform.pre_solve(true, &mut pre_solve_contact);
+        }
+        if let Some(platform) = self.one_way_platforms.get(&pre_solve_contact.fixture_b) {
+            platform.pre_solve(false, &mut pre_solve_contact);
+        }
+        for hook in self.pre_solve_hooks.iter_mut() {
+            hook.pre_solve(&mut pre_solve_contact);
+        }
+        pre_solve_contact.apply_to_ffi_contact(contact);
+    }
+    fn post_solve(&mut self, contact: &mut ffi_b2Contact, impulse: &ffi_b2ContactImpulse) {
+        if self.contact_impulse_thresholds.is_empty() && self.bodies_with_contacts.is_empty() {
+            return;
+        }
+
+        let ((fixture_a, body_a), (fixture_b, body_b)) = unsafe {
+            let mut contact = Pin::new_unchecked(&mut *contact);
+            (
+                fixture_entities(contact.as_mut().GetFixtureA().as_mut().unwrap()),
+                fixture_entities(contact.as_mut().GetFixtureB().as_mut().unwrap()),
+            )
+        };
+        let key = (
+            Entity::min(fixture_a, fixture_b),
+            Entity::max(fixture_a, fixture_b),
+        );
+
+        // keep the points and normal of contacts listed in b2Contacts components up to date
+        let is_listed = self.bodies_with_contacts.contains(&body_a)
+            || self.bodies_with_contacts.contains(&body_b);
+        if is_listed {
+            if let Some(fixture_contact) = self.fixture_contacts.get_mut(&key) {
+                *fixture_contact = b2Contact::from_ffi_contact(contact);
+            }
+        }
+
+        let threshold = [fixture_a, fixture_b]
+            .iter()
+            .filter_map(|fixture| self.contact_impulse_thresholds.get(fixture))
+            .copied()
+            .reduce(f32::max);
+        if let None = threshold {
+            return;
+        }
+
+        let impulse = read_contact_impulse(impulse);
+        let strongest_impulse =
+            max_normal_impulse(&impulse.normal_impulses[..impulse.count as usize]);
+        if strongest_impulse < threshold.unwrap() {
+            return;
+        }
+
+        // the world may be stepped several times per frame, keep the strongest impulse
+        let is_stronger = self
+            .contact_impulses
+            .get(&key)
+            .map_or(true, |i| strongest_impulse > i.max_normal_impulse());
+        if !is_stronger {
+            return;
+        }
+
+        // only compute the world manifold for impulses that are going to be reported
+        let contact = match self.fixture_contacts.get(&key) {
+            Some(fixture_contact) if is_listed => *fixture_contact,
+            _ => b2Contact::from_ffi_contact(contact),
+        };
+        self.contact_impulses
+            .insert(key, b2ContactImpulse::new(&contact, &impulse));
     }
-    fn pre_solve(&mut self, _contact: &mut ffi_b2Contact, _old_manifold: &b2Manifold) {}
-    fn post_solve(&mut self, _contact: &mut ffi_b2Contact, _impulse: &b2ContactImpulse) {}
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 
 use autocxx::WithinBox;
@@ -6,10 +6,12 @@ use bevy::math::Vec2;
 use bevy::prelude::{Component, Entity};
 
 use libliquidfun_sys::box2d::ffi::{
-    b2Contact as ffi_b2Contact, b2ParticleBodyContact as ffi_b2ParticleBodyContact, b2WorldManifold,
+    b2Contact as ffi_b2Contact, b2ParticleBodyContact as ffi_b2ParticleBodyContact,
+    b2ParticleContact as ffi_b2ParticleContact, b2WorldManifold,
 };
 
-use crate::internal::to_Vec2;
+use crate::internal::{fixture_entities, manifold_point_count, to_Vec2, ContactImpulse};
+use crate::particles::b2ParticleFlags;
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone)]
@@ -19,6 +21,7 @@ pub struct b2Contact {
     pub body_a: Entity,
     pub body_b: Entity,
     pub points: [Vec2; 2],
+    pub point_count: usize,
     pub normal: Vec2,
 }
 
@@ -26,23 +29,10 @@ impl b2Contact {
     pub(crate) fn from_ffi_contact(contact: &mut ffi_b2Contact) -> Self {
         unsafe {
             let mut contact = Pin::new_unchecked(contact);
-            let mut fixture_a =
-                Pin::new_unchecked(contact.as_mut().GetFixtureA().as_mut().unwrap());
-            let mut fixture_b =
-                Pin::new_unchecked(contact.as_mut().GetFixtureB().as_mut().unwrap());
-            let mut body_a = Pin::new_unchecked(fixture_a.as_mut().GetBody().as_mut().unwrap());
-            let mut body_b = Pin::new_unchecked(fixture_b.as_mut().GetBody().as_mut().unwrap());
-
-            let fixture_a_entity = Entity::from_bits(
-                fixture_a.as_mut().GetUserData().get_unchecked_mut().pointer as u64,
-            );
-            let fixture_b_entity = Entity::from_bits(
-                fixture_b.as_mut().GetUserData().get_unchecked_mut().pointer as u64,
-            );
-            let body_a_entity =
-                Entity::from_bits(body_a.as_mut().GetUserData().get_unchecked_mut().pointer as u64);
-            let body_b_entity =
-                Entity::from_bits(body_b.as_mut().GetUserData().get_unchecked_mut().pointer as u64);
+            let (fixture_a_entity, body_a_entity) =
+                fixture_entities(contact.as_mut().GetFixtureA().as_mut().unwrap());
+            let (fixture_b_entity, body_b_entity) =
+                fixture_entities(contact.as_mut().GetFixtureB().as_mut().unwrap());
 
             let mut manifold = b2WorldManifold::new().within_box();
             let manifold_ptr = manifold.as_mut().get_unchecked_mut() as *mut b2WorldManifold;
@@ -50,6 +40,8 @@ impl b2Contact {
             let points = &manifold.points;
             let points = [to_Vec2(&points[0]), to_Vec2(&points[1])];
             let normal = to_Vec2(&manifold.normal);
+            let point_count =
+                manifold_point_count(contact.as_mut().GetManifold().as_ref().unwrap());
 
             b2Contact {
                 fixture_a: fixture_a_entity,
@@ -57,6 +49,7 @@ impl b2Contact {
                 body_a: body_a_entity,
                 body_b: body_b_entity,
                 points,
+                point_count,
                 normal,
             }
         }
@@ -70,6 +63,57 @@ impl b2Contact {
     }
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2ContactImpulse {
+    pub fixture_a: Entity,
+    pub fixture_b: Entity,
+    pub body_a: Entity,
+    pub body_b: Entity,
+    pub points: [Vec2; 2],
+    pub normal: Vec2,
+    pub point_count: usize,
+    pub normal_impulses: [f32; 2],
+    pub tangent_impulses: [f32; 2],
+}
+
+impl b2ContactImpulse {
+    pub(crate) fn new(contact: &b2Contact, impulse: &ContactImpulse) -> Self {
+        b2ContactImpulse {
+            fixture_a: contact.fixture_a,
+            fixture_b: contact.fixture_b,
+            body_a: contact.body_a,
+            body_b: contact.body_b,
+            points: contact.points,
+            normal: contact.normal,
+            point_count: impulse.count as usize,
+            normal_impulses: impulse.normal_impulses,
+            tangent_impulses: impulse.tangent_impulses,
+        }
+    }
+
+    /// The largest normal impulse over all manifold points.
+    pub fn max_normal_impulse(&self) -> f32 {
+        max_normal_impulse(&self.normal_impulses[..self.point_count])
+    }
+}
+
+pub(crate) fn max_normal_impulse(normal_impulses: &[f32]) -> f32 {
+    normal_impulses
+        .iter()
+        .fold(0., |max, impulse| f32::max(max, *impulse))
+}
+
+/// Reports contact impulses of this fixture as [`b2ContactImpulseEvent`]s, if the largest
+/// normal impulse of the contact reaches the given threshold. Contacts between fixtures without
+/// a threshold are not reported. If both fixtures of a contact have a threshold, the larger one
+/// is used.
+///
+/// [`b2ContactImpulseEvent`]: crate::dynamics::b2ContactImpulseEvent
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Copy, Clone)]
+pub struct b2ContactImpulseThreshold(pub f32);
+
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone)]
 pub struct b2ParticleBodyContact {
@@ -108,6 +152,28 @@ impl b2ParticleBodyContact {
     }
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2ParticleContact {
+    pub index_a: i32,
+    pub index_b: i32,
+    pub weight: f32,
+    pub normal: Vec2,
+    pub flags: b2ParticleFlags,
+}
+
+impl b2ParticleContact {
+    pub(crate) fn from_ffi_contact(contact: &ffi_b2ParticleContact) -> Self {
+        b2ParticleContact {
+            index_a: i32::from(contact.GetIndexA()),
+            index_b: i32::from(contact.GetIndexB()),
+            weight: contact.GetWeight(),
+            normal: to_Vec2(contact.GetNormal()),
+            flags: b2ParticleFlags::from_bits_truncate(u32::from(contact.GetFlags())),
+        }
+    }
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Component, Debug)]
 pub struct b2ParticleContacts {
@@ -131,3 +197,85 @@ impl b2ParticleContacts {
         &mut self.contacts
     }
 }
+
+/// The fixtures currently overlapping a sensor fixture. Inserted automatically on fixture
+/// entities whose definition has `is_sensor` set, and removed when it is unset.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Default)]
+pub struct b2SensorOverlaps {
+    overlaps: HashMap<Entity, Entity>,
+}
+
+impl b2SensorOverlaps {
+    /// The overlapping fixture entities, mapped to the entities of their bodies.
+    pub fn overlaps(&self) -> &HashMap<Entity, Entity> {
+        &self.overlaps
+    }
+
+    pub fn fixtures(&self) -> impl Iterator<Item = &Entity> {
+        self.overlaps.keys()
+    }
+
+    pub fn bodies(&self) -> HashSet<Entity> {
+        self.overlaps.values().copied().collect()
+    }
+
+    pub fn contains_fixture(&self, fixture: Entity) -> bool {
+        self.overlaps.contains_key(&fixture)
+    }
+
+    pub fn contains_body(&self, body: Entity) -> bool {
+        self.overlaps.values().any(|b| *b == body)
+    }
+
+    pub(crate) fn overlaps_mut(&mut self) -> &mut HashMap<Entity, Entity> {
+        &mut self.overlaps
+    }
+}
+
+/// A contact of a body with another body, as seen from the body holding the [`b2Contacts`]
+/// component.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2BodyContact {
+    /// The fixture of this body that is touching.
+    pub fixture: Entity,
+    pub other_fixture: Entity,
+    pub other_body: Entity,
+    pub points: [Vec2; 2],
+    pub point_count: usize,
+
+    /// The contact normal, pointing from this body to the other body.
+    pub normal: Vec2,
+}
+
+/// Opt-in component for body entities that lists everything the body currently touches.
+/// Updated after each physics step. Contacts with sensor fixtures are not listed, see
+/// [`b2SensorOverlaps`] for those.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Default)]
+pub struct b2Contacts {
+    contacts: Vec<b2BodyContact>,
+}
+
+impl b2Contacts {
+    pub fn contacts(&self) -> &Vec<b2BodyContact> {
+        &self.contacts
+    }
+
+    pub fn bodies(&self) -> HashSet<Entity> {
+        self.contacts.iter().map(|c| c.other_body).collect()
+    }
+
+    pub fn fixtures(&self) -> HashSet<Entity> {
+        self.contacts.iter().map(|c| c.other_fixture).collect()
+    }
+
+    pub fn is_touching(&self, body: Entity) -> bool {
+        self.contacts.iter().any(|c| c.other_body == body)
+    }
+
+    pub(crate) fn contacts_mut(&mut self) -> &mut Vec<b2BodyContact> {
+        &mut self.contacts
+    }
+}
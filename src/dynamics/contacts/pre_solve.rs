@@ -0,0 +1,182 @@
+use std::fmt::Debug;
+use std::pin::Pin;
+
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity};
+
+use libliquidfun_sys::box2d::ffi::b2Contact as ffi_b2Contact;
+
+use crate::dynamics::b2Contact;
+use crate::internal::{manifold_point_count, to_Vec2, to_b2Vec2};
+
+/// A contact that is about to be solved. Changes made through the setters are applied to the
+/// contact for the current time step only.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Copy, Clone)]
+pub struct b2PreSolveContact {
+    pub fixture_a: Entity,
+    pub fixture_b: Entity,
+    pub body_a: Entity,
+    pub body_b: Entity,
+    pub points: [Vec2; 2],
+    pub normal: Vec2,
+    pub point_count: usize,
+
+    /// The velocity of bodyB relative to bodyA at the contact points.
+    pub relative_velocity: Vec2,
+
+    enabled: bool,
+    friction: f32,
+    restitution: f32,
+    tangent_speed: f32,
+}
+
+impl b2PreSolveContact {
+    pub(crate) fn from_ffi_contact(contact: &mut ffi_b2Contact) -> Self {
+        let b2_contact = b2Contact::from_ffi_contact(contact);
+        unsafe {
+            let mut contact = Pin::new_unchecked(contact);
+            let point_count =
+                manifold_point_count(contact.as_mut().GetManifold().as_ref().unwrap());
+
+            let mut fixture_a =
+                Pin::new_unchecked(contact.as_mut().GetFixtureA().as_mut().unwrap());
+            let mut fixture_b =
+                Pin::new_unchecked(contact.as_mut().GetFixtureB().as_mut().unwrap());
+            let body_a = Pin::new_unchecked(fixture_a.as_mut().GetBody().as_mut().unwrap());
+            let body_b = Pin::new_unchecked(fixture_b.as_mut().GetBody().as_mut().unwrap());
+
+            let relative_velocity = if point_count == 0 {
+                to_Vec2(body_b.as_ref().GetLinearVelocity())
+                    - to_Vec2(body_a.as_ref().GetLinearVelocity())
+            } else {
+                b2_contact.points[..point_count]
+                    .iter()
+                    .map(|point| {
+                        let point = to_b2Vec2(point);
+                        to_Vec2(&body_b.as_ref().GetLinearVelocityFromWorldPoint(&point))
+                            - to_Vec2(&body_a.as_ref().GetLinearVelocityFromWorldPoint(&point))
+                    })
+                    .sum::<Vec2>()
+                    / point_count as f32
+            };
+
+            b2PreSolveContact {
+                fixture_a: b2_contact.fixture_a,
+                fixture_b: b2_contact.fixture_b,
+                body_a: b2_contact.body_a,
+                body_b: b2_contact.body_b,
+                points: b2_contact.points,
+                normal: b2_contact.normal,
+                point_count,
+                relative_velocity,
+                enabled: contact.as_ref().IsEnabled(),
+                friction: contact.as_ref().GetFriction(),
+                restitution: contact.as_ref().GetRestitution(),
+                tangent_speed: contact.as_ref().GetTangentSpeed(),
+            }
+        }
+    }
+
+    pub(crate) fn apply_to_ffi_contact(&self, contact: &mut ffi_b2Contact) {
+        let mut contact = unsafe { Pin::new_unchecked(contact) };
+        contact.as_mut().SetEnabled(self.enabled);
+        contact.as_mut().SetFriction(self.friction);
+        contact.as_mut().SetRestitution(self.restitution);
+        contact.as_mut().SetTangentSpeed(self.tangent_speed);
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Disable the contact for the current time step.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn friction(&self) -> f32 {
+        self.friction
+    }
+
+    /// Override the default friction mixture.
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction;
+    }
+
+    pub fn restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    /// Override the default restitution mixture.
+    pub fn set_restitution(&mut self, restitution: f32) {
+        self.restitution = restitution;
+    }
+
+    pub fn tangent_speed(&self) -> f32 {
+        self.tangent_speed
+    }
+
+    /// Set the desired tangent speed for a conveyor belt behavior. In meters per second.
+    pub fn set_tangent_speed(&mut self, tangent_speed: f32) {
+        self.tangent_speed = tangent_speed;
+    }
+}
+
+/// Called for every contact that is about to be solved. Register hooks with
+/// [`b2World::add_pre_solve_hook`].
+///
+/// [`b2World::add_pre_solve_hook`]: crate::dynamics::b2World::add_pre_solve_hook
+#[allow(non_camel_case_types)]
+pub trait b2PreSolveHook: Debug {
+    fn pre_solve(&mut self, contact: &mut b2PreSolveContact);
+}
+
+/// Lets bodies pass through a fixture from below while still colliding with it from above.
+/// Add this component to the fixture entity of the platform.
+#[derive(Component, Debug, Copy, Clone)]
+pub struct OneWayPlatform {
+    /// The direction in which bodies can pass through the platform, in world coordinates.
+    pub up: Vec2,
+
+    /// The minimum cosine of the angle between the contact normal and `up` for the contact to be
+    /// solved. Contacts with the sides or the bottom of the platform are not solved. The default
+    /// of 0.5 only solves contacts with normals within 60° of `up`.
+    pub min_normal_cos: f32,
+
+    /// The speed along `up`, in meters per second, above which bodies keep passing through the
+    /// platform even when they touch its top. This prevents bodies that are still moving up
+    /// through the platform from being pushed on top of it.
+    pub pass_through_speed: f32,
+}
+
+impl Default for OneWayPlatform {
+    fn default() -> Self {
+        Self {
+            up: Vec2::Y,
+            min_normal_cos: 0.5,
+            pass_through_speed: 0.5,
+        }
+    }
+}
+
+impl OneWayPlatform {
+    /// Whether the contact between the platform and the other fixture should be solved.
+    /// `normal` points from the platform to the other fixture and `relative_velocity` is the
+    /// velocity of the other body relative to the platform.
+    pub fn should_collide(&self, normal: Vec2, relative_velocity: Vec2) -> bool {
+        let up = self.up.normalize_or_zero();
+        normal.dot(up) > self.min_normal_cos && relative_velocity.dot(up) < self.pass_through_speed
+    }
+
+    pub(crate) fn pre_solve(&self, is_fixture_a: bool, contact: &mut b2PreSolveContact) {
+        let (normal, relative_velocity) = if is_fixture_a {
+            (contact.normal, contact.relative_velocity)
+        } else {
+            (-contact.normal, -contact.relative_velocity)
+        };
+        if !self.should_collide(normal, relative_velocity) {
+            contact.set_enabled(false);
+        }
+    }
+}
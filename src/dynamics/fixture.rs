@@ -11,6 +11,9 @@ use std::pin::Pin;
 pub struct b2Fixture {
     body: Entity,
     def: b2FixtureDef,
+
+    /// The shape the fixture was last created with in the physics world.
+    created_shape: b2Shape,
 }
 
 impl b2Fixture {
@@ -18,6 +21,7 @@ impl b2Fixture {
         b2Fixture {
             body,
             def: (*fixture_def).clone(),
+            created_shape: fixture_def.shape.clone(),
         }
     }
 
@@ -28,6 +32,44 @@ impl b2Fixture {
     pub fn def(&self) -> &b2FixtureDef {
         &self.def
     }
+
+    /// Changes to the material properties, the sensor flag and the filter are applied to the
+    /// physics world by `sync_fixtures_to_world`. Changing the shape recreates the fixture.
+    pub fn def_mut(&mut self) -> &mut b2FixtureDef {
+        &mut self.def
+    }
+
+    pub(crate) fn shape_changed(&self) -> bool {
+        self.created_shape != self.def.shape
+    }
+
+    pub(crate) fn mark_shape_created(&mut self) {
+        self.created_shape = self.def.shape.clone();
+    }
+
+    pub(crate) fn sync_to_world(&self, mut fixture_ptr: Pin<&mut ffi::b2Fixture>) {
+        fixture_ptr.as_mut().SetFriction(self.def.friction);
+        fixture_ptr.as_mut().SetRestitution(self.def.restitution);
+        fixture_ptr
+            .as_mut()
+            .SetRestitutionThreshold(self.def.restitution_threshold);
+        fixture_ptr.as_mut().SetSensor(self.def.is_sensor);
+
+        if fixture_ptr.as_ref().GetDensity() != self.def.density {
+            fixture_ptr.as_mut().SetDensity(self.def.density);
+            unsafe {
+                let body = fixture_ptr.as_mut().GetBody().as_mut().unwrap();
+                Pin::new_unchecked(body).ResetMassData();
+            }
+        }
+
+        if b2Filter::from_ffi(fixture_ptr.as_ref().GetFilterData()) != self.def.filter {
+            fixture_ptr
+                .as_mut()
+                .SetFilterData(&self.def.filter.to_ffi());
+            fixture_ptr.as_mut().Refilter();
+        }
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -43,7 +85,7 @@ pub struct b2FixtureDef {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct b2Filter {
     pub category: u16,
     pub mask: u16,
@@ -59,17 +101,17 @@ impl b2FixtureDef {
         }
     }
 
-    pub(crate) fn to_ffi(&self) -> Pin<Box<ffi::b2FixtureDef>> {
+    /// Box2D clones `shape` when the fixture is created, so it only has to outlive the
+    /// `CreateFixture` call.
+    pub(crate) fn to_ffi(&self, shape: &ffi::b2Shape) -> Pin<Box<ffi::b2FixtureDef>> {
         let mut b2fixture_def = ffi::b2FixtureDef::new().within_box();
-        b2fixture_def.shape = self.shape.to_ffi();
+        b2fixture_def.shape = shape;
         b2fixture_def.density = self.density;
         b2fixture_def.friction = self.friction;
         b2fixture_def.restitution = self.restitution;
         b2fixture_def.restitutionThreshold = self.restitution_threshold;
         b2fixture_def.isSensor = self.is_sensor;
-        b2fixture_def.filter.categoryBits = uint16::from(self.filter.category);
-        b2fixture_def.filter.maskBits = uint16::from(self.filter.mask);
-        b2fixture_def.filter.groupIndex = int16::from(self.filter.group_index);
+        b2fixture_def.filter = self.filter.to_ffi();
         return b2fixture_def;
     }
 }
@@ -88,6 +130,24 @@ impl Default for b2FixtureDef {
     }
 }
 
+impl b2Filter {
+    pub(crate) fn from_ffi(filter: &ffi::b2Filter) -> Self {
+        Self {
+            category: u16::from(filter.categoryBits),
+            mask: u16::from(filter.maskBits),
+            group_index: i16::from(filter.groupIndex),
+        }
+    }
+
+    pub(crate) fn to_ffi(&self) -> ffi::b2Filter {
+        let mut filter: ffi::b2Filter = unsafe { std::mem::zeroed() };
+        filter.categoryBits = uint16::from(self.category);
+        filter.maskBits = uint16::from(self.mask);
+        filter.groupIndex = int16::from(self.group_index);
+        filter
+    }
+}
+
 impl Default for b2Filter {
     fn default() -> Self {
         Self {
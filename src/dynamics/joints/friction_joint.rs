@@ -0,0 +1,121 @@
+use std::pin::Pin;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity, World};
+use libliquidfun_sys::box2d::ffi;
+
+use crate::dynamics::{b2Joint, b2JointType, b2World, JointPtr};
+use crate::internal::to_b2Vec2;
+
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug)]
+pub struct b2FrictionJoint {
+    /// The local anchor point relative to bodyA's origin.
+    local_anchor_a: Vec2,
+
+    /// The local anchor point relative to bodyB's origin.
+    local_anchor_b: Vec2,
+
+    /// The maximum friction force in N.
+    pub max_force: f32,
+
+    /// The maximum friction torque in N-m.
+    pub max_torque: f32,
+}
+
+impl b2FrictionJoint {
+    pub fn new(def: &b2FrictionJointDef) -> Self {
+        Self {
+            local_anchor_a: def.local_anchor_a,
+            local_anchor_b: def.local_anchor_b,
+            max_force: def.max_force,
+            max_torque: def.max_torque,
+        }
+    }
+
+    pub(crate) fn create_ffi_joint<'a>(
+        &self,
+        b2_world: &mut b2World,
+        body_a: Entity,
+        body_b: Entity,
+        collide_connected: bool,
+    ) -> JointPtr<'a> {
+        unsafe {
+            let body_a = b2_world.get_body_ptr_mut(body_a).unwrap().as_mut();
+            let body_a = body_a.get_unchecked_mut() as *mut ffi::b2Body;
+            let body_b = b2_world.get_body_ptr_mut(body_b).unwrap().as_mut();
+            let body_b = body_b.get_unchecked_mut() as *mut ffi::b2Body;
+            let ffi_world = b2_world.get_world_ptr().as_mut();
+            let ffi_joint = ffi::CreateFrictionJoint(
+                ffi_world,
+                body_a,
+                body_b,
+                collide_connected,
+                to_b2Vec2(&self.local_anchor_a),
+                to_b2Vec2(&self.local_anchor_b),
+                self.max_force,
+                self.max_torque,
+            );
+            let ffi_joint = Pin::new_unchecked(ffi_joint.as_mut().unwrap());
+            JointPtr::Friction(ffi_joint)
+        }
+    }
+
+    pub(crate) fn sync_to_world(&self, mut joint_ptr: Pin<&mut ffi::b2FrictionJoint>) {
+        joint_ptr.as_mut().SetMaxForce(self.max_force);
+        joint_ptr.as_mut().SetMaxTorque(self.max_torque);
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Clone)]
+pub struct b2FrictionJointDef {
+    /// The local anchor point relative to bodyA's origin.
+    pub local_anchor_a: Vec2,
+
+    /// The local anchor point relative to bodyB's origin.
+    pub local_anchor_b: Vec2,
+
+    /// The maximum friction force in N.
+    pub max_force: f32,
+
+    /// The maximum friction torque in N-m.
+    pub max_torque: f32,
+}
+
+pub struct CreateFrictionJoint {
+    body_a: Entity,
+    body_b: Entity,
+    collide_connected: bool,
+    def: b2FrictionJointDef,
+}
+
+impl CreateFrictionJoint {
+    pub fn new(
+        body_a: Entity,
+        body_b: Entity,
+        collide_connected: bool,
+        def: &b2FrictionJointDef,
+    ) -> Self {
+        Self {
+            body_a,
+            body_b,
+            collide_connected,
+            def: def.clone(),
+        }
+    }
+}
+
+impl EntityCommand for CreateFrictionJoint {
+    fn apply(self, id: Entity, world: &mut World) {
+        let joint = b2Joint::new(
+            b2JointType::Friction,
+            self.body_a,
+            self.body_b,
+            self.collide_connected,
+        );
+        let friction_joint = b2FrictionJoint::new(&self.def);
+        world.entity_mut(id).insert((joint, friction_joint));
+    }
+}
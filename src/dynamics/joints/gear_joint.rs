@@ -0,0 +1,185 @@
+use std::pin::Pin;
+
+use bevy::ecs::system::EntityCommand;
+use bevy::prelude::{warn, Component, Entity, World};
+use libliquidfun_sys::box2d::ffi;
+
+use crate::dynamics::{b2Joint, b2JointType, b2World, JointPtr};
+
+/// A gear joint couples two existing revolute and/or prismatic joints. Both referenced joints
+/// need to be attached to a static body as bodyA. The gear joint connects bodyB of the first
+/// joint to bodyB of the second joint.
+///
+/// Despawning either of the referenced joints also destroys and despawns the gear joint.
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug)]
+pub struct b2GearJoint {
+    /// The first revolute/prismatic joint attached to the gear joint.
+    joint_a: Entity,
+
+    /// The second revolute/prismatic joint attached to the gear joint.
+    joint_b: Entity,
+
+    /// The gear ratio.
+    pub ratio: f32,
+}
+
+impl b2GearJoint {
+    pub fn new(joint_a: Entity, joint_b: Entity, def: &b2GearJointDef) -> Self {
+        Self {
+            joint_a,
+            joint_b,
+            ratio: def.ratio,
+        }
+    }
+
+    pub fn joint_a(&self) -> Entity {
+        self.joint_a
+    }
+
+    pub fn joint_b(&self) -> Entity {
+        self.joint_b
+    }
+
+    /// Creates the gear joint in the physics world. Returns `None`, after logging a warning, if
+    /// one of the bodies or referenced joints does not exist in the physics world.
+    pub(crate) fn create_ffi_joint<'a>(
+        &self,
+        b2_world: &mut b2World,
+        body_a: Entity,
+        body_b: Entity,
+        collide_connected: bool,
+    ) -> Option<JointPtr<'a>> {
+        unsafe {
+            let body_a_ptr = b2_world.get_body_ptr_mut(body_a);
+            if let None = body_a_ptr {
+                warn!(
+                    "Gear joint body {:?} does not exist in the physics world",
+                    body_a
+                );
+                return None;
+            }
+            let body_a = body_a_ptr.unwrap().as_mut().get_unchecked_mut() as *mut ffi::b2Body;
+
+            let body_b_ptr = b2_world.get_body_ptr_mut(body_b);
+            if let None = body_b_ptr {
+                warn!(
+                    "Gear joint body {:?} does not exist in the physics world",
+                    body_b
+                );
+                return None;
+            }
+            let body_b = body_b_ptr.unwrap().as_mut().get_unchecked_mut() as *mut ffi::b2Body;
+
+            let joint_a_ptr = b2_world.get_joint_ptr(&self.joint_a);
+            if let None = joint_a_ptr {
+                warn!(
+                    "Gear joint target {:?} does not exist in the physics world",
+                    self.joint_a
+                );
+                return None;
+            }
+            let joint_a = joint_a_ptr.unwrap().as_ffi_joint_ptr();
+
+            let joint_b_ptr = b2_world.get_joint_ptr(&self.joint_b);
+            if let None = joint_b_ptr {
+                warn!(
+                    "Gear joint target {:?} does not exist in the physics world",
+                    self.joint_b
+                );
+                return None;
+            }
+            let joint_b = joint_b_ptr.unwrap().as_ffi_joint_ptr();
+
+            let ffi_world = b2_world.get_world_ptr().as_mut();
+            let ffi_joint = ffi::CreateGearJoint(
+                ffi_world,
+                body_a,
+                body_b,
+                collide_connected,
+                joint_a,
+                joint_b,
+                self.ratio,
+            );
+            let ffi_joint = Pin::new_unchecked(ffi_joint.as_mut().unwrap());
+            Some(JointPtr::Gear(ffi_joint))
+        }
+    }
+
+    pub(crate) fn sync_to_world(&self, mut joint_ptr: Pin<&mut ffi::b2GearJoint>) {
+        joint_ptr.as_mut().SetRatio(self.ratio);
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub struct b2GearJointDef {
+    /// The gear ratio.
+    pub ratio: f32,
+}
+
+impl Default for b2GearJointDef {
+    fn default() -> Self {
+        Self { ratio: 1. }
+    }
+}
+
+pub struct CreateGearJoint {
+    joint_a: Entity,
+    joint_b: Entity,
+    collide_connected: bool,
+    def: b2GearJointDef,
+}
+
+impl CreateGearJoint {
+    pub fn new(
+        joint_a: Entity,
+        joint_b: Entity,
+        collide_connected: bool,
+        def: &b2GearJointDef,
+    ) -> Self {
+        Self {
+            joint_a,
+            joint_b,
+            collide_connected,
+            def: def.clone(),
+        }
+    }
+}
+
+impl EntityCommand for CreateGearJoint {
+    fn apply(self, id: Entity, world: &mut World) {
+        let joint_a = world.get::<b2Joint>(self.joint_a);
+        let joint_b = world.get::<b2Joint>(self.joint_b);
+        if joint_a.is_none() || joint_b.is_none() {
+            warn!(
+                "CreateGearJoint needs two entities with a b2Joint, got {:?} and {:?}",
+                self.joint_a, self.joint_b
+            );
+            return;
+        }
+        let joint_a = joint_a.unwrap();
+        let joint_b = joint_b.unwrap();
+
+        let is_gearable = |joint: &b2Joint| {
+            matches!(
+                joint.joint_type(),
+                b2JointType::Revolute | b2JointType::Prismatic
+            )
+        };
+        if !is_gearable(joint_a) || !is_gearable(joint_b) {
+            warn!(
+                "CreateGearJoint needs two revolute or prismatic joints, got {:?} and {:?}",
+                joint_a.joint_type(),
+                joint_b.joint_type()
+            );
+            return;
+        }
+
+        let body_a = *joint_a.body_b();
+        let body_b = *joint_b.body_b();
+        let joint = b2Joint::new(b2JointType::Gear, body_a, body_b, self.collide_connected);
+        let gear_joint = b2GearJoint::new(self.joint_a, self.joint_b, &self.def);
+        world.entity_mut(id).insert((joint, gear_joint));
+    }
+}
@@ -1,8 +1,11 @@
 use std::pin::Pin;
 
+use bevy::math::Vec2;
 use bevy::prelude::{Component, Entity};
 use libliquidfun_sys::box2d::ffi;
 
+use crate::internal::to_Vec2;
+
 #[allow(non_camel_case_types)]
 #[derive(Component, Debug)]
 pub struct b2Joint {
@@ -10,6 +13,18 @@ pub struct b2Joint {
     body_a: Entity,
     body_b: Entity,
     collide_connected: bool,
+
+    /// The anchor point on bodyA in world coordinates.
+    anchor_a: Vec2,
+
+    /// The anchor point on bodyB in world coordinates.
+    anchor_b: Vec2,
+
+    /// The reaction force on bodyB at the joint anchor in Newtons.
+    reaction_force: Vec2,
+
+    /// The reaction torque on bodyB in N*m.
+    reaction_torque: f32,
 }
 
 impl b2Joint {
@@ -24,6 +39,10 @@ impl b2Joint {
             body_a,
             body_b,
             collide_connected,
+            anchor_a: Vec2::ZERO,
+            anchor_b: Vec2::ZERO,
+            reaction_force: Vec2::ZERO,
+            reaction_torque: 0.,
         }
     }
 
@@ -42,6 +61,61 @@ impl b2Joint {
     pub fn collide_connected(&self) -> bool {
         self.collide_connected
     }
+
+    pub fn anchor_a(&self) -> Vec2 {
+        self.anchor_a
+    }
+
+    pub fn anchor_b(&self) -> Vec2 {
+        self.anchor_b
+    }
+
+    pub fn reaction_force(&self) -> Vec2 {
+        self.reaction_force
+    }
+
+    pub fn reaction_torque(&self) -> f32 {
+        self.reaction_torque
+    }
+
+    pub(crate) fn sync_with_world(&mut self, joint_ptr: &mut JointPtr, inv_dt: f32) {
+        unsafe {
+            let ffi_joint = joint_ptr.as_ffi_joint_ptr().as_ref().unwrap();
+            self.anchor_a = to_Vec2(&ffi_joint.GetAnchorA());
+            self.anchor_b = to_Vec2(&ffi_joint.GetAnchorB());
+            self.reaction_force = to_Vec2(&ffi_joint.GetReactionForce(inv_dt));
+            self.reaction_torque = ffi_joint.GetReactionTorque(inv_dt);
+        }
+    }
+}
+
+/// Makes a joint break when the reaction force or torque it exerts exceeds the given limits.
+/// A broken joint is destroyed and despawned, and a [`b2JointBrokenEvent`] is sent.
+///
+/// [`b2JointBrokenEvent`]: crate::dynamics::b2JointBrokenEvent
+#[allow(non_camel_case_types)]
+#[derive(Component, Debug, Copy, Clone)]
+pub struct b2Breakable {
+    /// The maximum reaction force in Newtons.
+    pub max_force: f32,
+
+    /// The maximum reaction torque in N*m.
+    pub max_torque: f32,
+}
+
+impl Default for b2Breakable {
+    fn default() -> Self {
+        Self {
+            max_force: f32::MAX,
+            max_torque: f32::MAX,
+        }
+    }
+}
+
+impl b2Breakable {
+    pub fn is_exceeded_by(&self, reaction_force: Vec2, reaction_torque: f32) -> bool {
+        reaction_force.length() > self.max_force || reaction_torque.abs() > self.max_torque
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -49,29 +123,86 @@ impl b2Joint {
 pub enum b2JointType {
     Revolute,
     Prismatic,
-    _Distance, // TODO
-    _Pulley,
-    _Mouse,
-    _Gear,
-    _Wheel,
-    _Weld,
-    _Friction,
-    _Rope,
-    _Motor,
-    _Area,
+    Distance,
+    Weld,
+    Wheel,
+    Mouse,
+    Pulley,
+    Gear,
+    Friction,
+    Motor,
 }
 
 pub(crate) enum JointPtr<'a> {
     Revolute(Pin<&'a mut ffi::b2RevoluteJoint>),
     Prismatic(Pin<&'a mut ffi::b2PrismaticJoint>),
-    _Distance, // TODO
-    _Pulley,
-    _Mouse,
-    _Gear,
-    _Wheel,
-    _Weld,
-    _Friction,
-    _Rope,
-    _Motor,
-    _Area,
+    Distance(Pin<&'a mut ffi::b2DistanceJoint>),
+    Weld(Pin<&'a mut ffi::b2WeldJoint>),
+    Wheel(Pin<&'a mut ffi::b2WheelJoint>),
+    Mouse(Pin<&'a mut ffi::b2MouseJoint>),
+    Pulley(Pin<&'a mut ffi::b2PulleyJoint>),
+    Gear(Pin<&'a mut ffi::b2GearJoint>),
+    Friction(Pin<&'a mut ffi::b2FrictionJoint>),
+    Motor(Pin<&'a mut ffi::b2MotorJoint>),
+}
+
+impl<'a> JointPtr<'a> {
+    pub(crate) fn as_ffi_joint_ptr(&mut self) -> *mut ffi::b2Joint {
+        unsafe {
+            match self {
+                JointPtr::Revolute(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2RevoluteJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Prismatic(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2PrismaticJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Distance(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2DistanceJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Weld(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2WeldJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Wheel(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2WheelJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Mouse(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2MouseJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Pulley(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2PulleyJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Gear(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2GearJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Friction(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2FrictionJoint
+                    as *mut ffi::b2Joint,
+                JointPtr::Motor(joint_ptr) => joint_ptr.as_mut().get_unchecked_mut()
+                    as *mut ffi::b2MotorJoint
+                    as *mut ffi::b2Joint,
+            }
+        }
+    }
+}
+
+/// Calculates the spring stiffness and damping of a soft linear constraint from a frequency
+/// and a damping ratio, using the effective mass of the two bodies. Static bodies have zero mass.
+pub fn linear_stiffness(
+    frequency_hertz: f32,
+    damping_ratio: f32,
+    mass_a: f32,
+    mass_b: f32,
+) -> (f32, f32) {
+    let mass = if mass_a > 0. && mass_b > 0. {
+        mass_a * mass_b / (mass_a + mass_b)
+    } else if mass_a > 0. {
+        mass_a
+    } else {
+        mass_b
+    };
+
+    let omega = 2. * std::f32::consts::PI * frequency_hertz;
+    let stiffness = mass * omega * omega;
+    let damping = 2. * mass * damping_ratio * omega;
+    (stiffness, damping)
 }
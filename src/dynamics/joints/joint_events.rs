@@ -0,0 +1,16 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, Event};
+
+/// Sent when a joint with a [`b2Breakable`] component breaks. Gear joints referring to the
+/// broken joint are destroyed along with it and are reported with their own event.
+///
+/// [`b2Breakable`]: crate::dynamics::b2Breakable
+#[allow(non_camel_case_types)]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct b2JointBrokenEvent {
+    pub joint: Entity,
+    pub body_a: Entity,
+    pub body_b: Entity,
+    pub reaction_force: Vec2,
+    pub reaction_torque: f32,
+}
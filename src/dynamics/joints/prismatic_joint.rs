@@ -37,6 +37,15 @@ pub struct b2PrismaticJoint {
 
     /// The desired motor speed in radians per second.
     pub motor_speed: f32,
+
+    /// The current joint translation, usually in meters.
+    joint_translation: f32,
+
+    /// The current joint translation speed, usually in meters per second.
+    joint_speed: f32,
+
+    /// The current motor force, usually in N.
+    motor_force: f32,
 }
 
 impl b2PrismaticJoint {
@@ -52,9 +61,28 @@ impl b2PrismaticJoint {
             enable_motor: def.enable_motor,
             max_motor_force: def.max_motor_force,
             motor_speed: def.motor_speed,
+            joint_translation: 0.,
+            joint_speed: 0.,
+            motor_force: 0.,
         }
     }
 
+    pub fn local_axis_a(&self) -> Vec2 {
+        self.local_axis_a
+    }
+
+    pub fn joint_translation(&self) -> f32 {
+        self.joint_translation
+    }
+
+    pub fn joint_speed(&self) -> f32 {
+        self.joint_speed
+    }
+
+    pub fn motor_force(&self) -> f32 {
+        self.motor_force
+    }
+
     pub(crate) fn create_ffi_joint<'a>(
         &self,
         b2_world: &mut b2World,
@@ -98,6 +126,16 @@ impl b2PrismaticJoint {
         joint_ptr.as_mut().SetMaxMotorForce(self.max_motor_force);
         joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
     }
+
+    pub(crate) fn sync_with_world(
+        &mut self,
+        joint_ptr: Pin<&mut ffi::b2PrismaticJoint>,
+        inv_dt: f32,
+    ) {
+        self.joint_translation = joint_ptr.as_ref().GetJointTranslation();
+        self.joint_speed = joint_ptr.as_ref().GetJointSpeed();
+        self.motor_force = joint_ptr.as_ref().GetMotorForce(inv_dt);
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -38,6 +38,15 @@ pub struct b2RevoluteJoint {
     /// The maximum motor torque used to achieve the desired motor speed.
     /// Usually in N-m.
     pub max_motor_torque: f32,
+
+    /// The current joint angle in radians.
+    joint_angle: f32,
+
+    /// The current joint angle speed in radians per second.
+    joint_speed: f32,
+
+    /// The current motor torque, usually in N-m.
+    motor_torque: f32,
 }
 
 impl b2RevoluteJoint {
@@ -52,9 +61,28 @@ impl b2RevoluteJoint {
             enable_motor: def.enable_motor,
             motor_speed: def.motor_speed,
             max_motor_torque: def.max_motor_torque,
+            joint_angle: 0.,
+            joint_speed: 0.,
+            motor_torque: 0.,
         }
     }
 
+    pub fn reference_angle(&self) -> f32 {
+        self.reference_angle
+    }
+
+    pub fn joint_angle(&self) -> f32 {
+        self.joint_angle
+    }
+
+    pub fn joint_speed(&self) -> f32 {
+        self.joint_speed
+    }
+
+    pub fn motor_torque(&self) -> f32 {
+        self.motor_torque
+    }
+
     pub(crate) fn create_ffi_joint<'a>(
         &self,
         b2_world: &mut b2World,
@@ -97,6 +125,16 @@ impl b2RevoluteJoint {
         joint_ptr.as_mut().SetMaxMotorTorque(self.max_motor_torque);
         joint_ptr.as_mut().SetMotorSpeed(self.motor_speed);
     }
+
+    pub(crate) fn sync_with_world(
+        &mut self,
+        joint_ptr: Pin<&mut ffi::b2RevoluteJoint>,
+        inv_dt: f32,
+    ) {
+        self.joint_angle = joint_ptr.as_ref().GetJointAngle();
+        self.joint_speed = joint_ptr.as_ref().GetJointSpeed();
+        self.motor_torque = joint_ptr.as_ref().GetMotorTorque(inv_dt);
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -0,0 +1,90 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::collision::b2Shape;
+use crate::dynamics::{
+    b2DistanceResult, b2OverlapHit, b2QueryCallback, b2RayCastCallback, b2RayCastFilter,
+    b2ShapeCastHit, b2TimeOfImpactResult, b2World,
+};
+
+/// Read-only access to the scene queries of the [`b2World`].
+///
+/// Use this instead of `NonSendMut<b2World>` in systems that only cast rays or query the world.
+/// The world is not `Send`, so these systems still run on the main thread, one at a time.
+/// Since they only read the world, they don't conflict with each other or with other systems
+/// reading the world, and Bevy doesn't need to order them.
+#[derive(SystemParam)]
+pub struct PhysicsQuery<'w> {
+    b2_world: NonSend<'w, b2World<'static>>,
+}
+
+impl<'w> PhysicsQuery<'w> {
+    pub fn ray_cast<T: b2RayCastCallback + 'static>(
+        &self,
+        callback: T,
+        start: &Vec2,
+        end: &Vec2,
+    ) -> T::Result {
+        self.b2_world.ray_cast(callback, start, end)
+    }
+
+    pub fn ray_cast_with_filter<T: b2RayCastCallback + 'static, F: b2RayCastFilter + 'static>(
+        &self,
+        callback: T,
+        filter: F,
+        start: &Vec2,
+        end: &Vec2,
+    ) -> T::Result {
+        self.b2_world
+            .ray_cast_with_filter(callback, filter, start, end)
+    }
+
+    pub fn query_aabb<T: b2QueryCallback, F: b2RayCastFilter>(
+        &self,
+        min: &Vec2,
+        max: &Vec2,
+        callback: T,
+        filter: F,
+    ) -> T::Result {
+        self.b2_world.query_aabb(min, max, callback, filter)
+    }
+
+    pub fn shape_cast<F: b2RayCastFilter>(
+        &self,
+        shape: &b2Shape,
+        position: Vec2,
+        angle: f32,
+        translation: Vec2,
+        filter: F,
+    ) -> Vec<b2ShapeCastHit> {
+        self.b2_world
+            .shape_cast(shape, position, angle, translation, filter)
+    }
+
+    pub fn query_point<F: b2RayCastFilter>(&self, point: Vec2, filter: F) -> Vec<b2OverlapHit> {
+        self.b2_world.query_point(point, filter)
+    }
+
+    pub fn query_shape<F: b2RayCastFilter>(
+        &self,
+        shape: &b2Shape,
+        position: Vec2,
+        angle: f32,
+        filter: F,
+    ) -> Vec<b2OverlapHit> {
+        self.b2_world.query_shape(shape, position, angle, filter)
+    }
+
+    pub fn distance(&self, fixture_a: Entity, fixture_b: Entity) -> Option<b2DistanceResult> {
+        self.b2_world.distance(fixture_a, fixture_b)
+    }
+
+    pub fn time_of_impact(
+        &self,
+        fixture_a: Entity,
+        fixture_b: Entity,
+        duration: f32,
+    ) -> Option<b2TimeOfImpactResult> {
+        self.b2_world.time_of_impact(fixture_a, fixture_b, duration)
+    }
+}
@@ -0,0 +1,273 @@
+use std::fmt::Debug;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use libliquidfun_sys::box2d::ffi;
+use libliquidfun_sys::box2d::ffi::b2Body as ffi_b2Body;
+use libliquidfun_sys::box2d::ffi::b2Fixture as ffi_b2Fixture;
+use libliquidfun_sys::box2d::ffi::int32;
+
+use crate::dynamics::b2RayCastFilter;
+use crate::internal::to_Vec2;
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub(crate) struct b2Query<T: b2QueryCallback, F: b2RayCastFilter> {
+    callback: T,
+    filter: F,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl<T: b2QueryCallback, F: b2RayCastFilter> b2Query<T, F> {
+    pub fn new(callback: T, filter: F, min: Vec2, max: Vec2) -> Self {
+        Self {
+            callback,
+            filter,
+            min,
+            max,
+        }
+    }
+
+    pub fn extract_hits(self) -> T::Result {
+        self.callback.into_result()
+    }
+
+    pub fn min(&self) -> Vec2 {
+        self.min
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.max
+    }
+
+    fn overlaps(&self, aabb: &ffi::b2AABB) -> bool {
+        let lower = to_Vec2(&aabb.lowerBound);
+        let upper = to_Vec2(&aabb.upperBound);
+        lower.cmple(self.max).all() && upper.cmpge(self.min).all()
+    }
+
+    /// Reports the fixture if any of its child AABBs overlaps the query AABB. Returns false if
+    /// the query was terminated.
+    pub fn query_fixture(
+        &mut self,
+        body_entity: Entity,
+        body: &ffi_b2Body,
+        fixture_entity: Entity,
+        fixture: &ffi_b2Fixture,
+    ) -> bool {
+        let child_count =
+            unsafe { i32::from(fixture.GetShape1().as_ref().unwrap().GetChildCount()) };
+        let overlaps = (0..child_count)
+            .any(|child_index| self.overlaps(fixture.GetAABB(int32::from(child_index))));
+        if !overlaps {
+            return true;
+        }
+
+        if !self
+            .filter
+            .should_use(body_entity, body, fixture_entity, fixture)
+        {
+            return true;
+        }
+
+        self.callback.report_fixture(body_entity, fixture_entity)
+    }
+
+    /// Whether particles of the given particle system should be reported, because either the
+    /// callback or the filter asks for them.
+    pub fn should_query_particle_system(&mut self, particle_system_entity: Entity) -> bool {
+        self.callback
+            .should_query_particle_system(particle_system_entity)
+            || self
+                .filter
+                .should_query_particle_system(particle_system_entity)
+    }
+
+    /// Reports a particle inside the query AABB. Returns false if the query was terminated.
+    pub fn query_particle(&mut self, particle_system_entity: Entity, particle_index: i32) -> bool {
+        if !self
+            .filter
+            .should_use_particle(particle_system_entity, particle_index)
+        {
+            return true;
+        }
+
+        self.callback
+            .report_particle(particle_system_entity, particle_index)
+    }
+}
+
+#[allow(non_camel_case_types)]
+pub trait b2QueryCallback: Debug {
+    type Result;
+
+    /// Called for each fixture whose AABB overlaps the query AABB. Return false to terminate
+    /// the query.
+    fn report_fixture(&mut self, body_entity: Entity, fixture_entity: Entity) -> bool;
+
+    /// Called for each particle inside the query AABB of the particle systems selected by
+    /// [`Self::should_query_particle_system`]. Return false to terminate the query.
+    fn report_particle(&mut self, _particle_system_entity: Entity, _particle_index: i32) -> bool {
+        true
+    }
+
+    /// Whether particles of the given particle system should be reported.
+    fn should_query_particle_system(&mut self, _particle_system_entity: Entity) -> bool {
+        false
+    }
+
+    fn into_result(self) -> Self::Result;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum b2QueryHit {
+    Fixture {
+        body_entity: Entity,
+        fixture_entity: Entity,
+    },
+    Particle {
+        particle_system_entity: Entity,
+        particle_index: i32,
+    },
+}
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct b2QueryAll {
+    include_particles: bool,
+    result: Vec<b2QueryHit>,
+}
+
+impl b2QueryAll {
+    pub fn new() -> Self {
+        Self {
+            include_particles: false,
+            result: Vec::new(),
+        }
+    }
+
+    /// Also report the particles of all particle systems inside the query AABB.
+    pub fn with_particles() -> Self {
+        Self {
+            include_particles: true,
+            result: Vec::new(),
+        }
+    }
+}
+
+impl b2QueryCallback for b2QueryAll {
+    type Result = Vec<b2QueryHit>;
+
+    fn report_fixture(&mut self, body_entity: Entity, fixture_entity: Entity) -> bool {
+        self.result.push(b2QueryHit::Fixture {
+            body_entity,
+            fixture_entity,
+        });
+        true
+    }
+
+    fn report_particle(&mut self, particle_system_entity: Entity, particle_index: i32) -> bool {
+        self.result.push(b2QueryHit::Particle {
+            particle_system_entity,
+            particle_index,
+        });
+        true
+    }
+
+    fn should_query_particle_system(&mut self, _particle_system_entity: Entity) -> bool {
+        self.include_particles
+    }
+
+    fn into_result(self) -> Self::Result {
+        self.result
+    }
+}
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct b2QueryAny {
+    include_particles: bool,
+    result: Option<b2QueryHit>,
+}
+
+impl b2QueryAny {
+    pub fn new() -> Self {
+        Self {
+            include_particles: false,
+            result: None,
+        }
+    }
+
+    /// Also consider the particles of all particle systems inside the query AABB.
+    pub fn with_particles() -> Self {
+        Self {
+            include_particles: true,
+            result: None,
+        }
+    }
+}
+
+impl b2QueryCallback for b2QueryAny {
+    type Result = Option<b2QueryHit>;
+
+    fn report_fixture(&mut self, body_entity: Entity, fixture_entity: Entity) -> bool {
+        self.result = Some(b2QueryHit::Fixture {
+            body_entity,
+            fixture_entity,
+        });
+        false
+    }
+
+    fn report_particle(&mut self, particle_system_entity: Entity, particle_index: i32) -> bool {
+        self.result = Some(b2QueryHit::Particle {
+            particle_system_entity,
+            particle_index,
+        });
+        false
+    }
+
+    fn should_query_particle_system(&mut self, _particle_system_entity: Entity) -> bool {
+        self.include_particles && self.result.is_none()
+    }
+
+    fn into_result(self) -> Self::Result {
+        self.result
+    }
+}
+
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct b2QueryUniqueBodies {
+    result: HashSet<Entity>,
+}
+
+impl b2QueryUniqueBodies {
+    pub fn new() -> Self {
+        Self {
+            result: HashSet::new(),
+        }
+    }
+}
+
+impl b2QueryCallback for b2QueryUniqueBodies {
+    type Result = HashSet<Entity>;
+
+    fn report_fixture(&mut self, body_entity: Entity, _fixture_entity: Entity) -> bool {
+        self.result.insert(body_entity);
+        true
+    }
+
+    fn into_result(self) -> Self::Result {
+        self.result
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub struct b2OverlapHit {
+    pub body_entity: Entity,
+    pub fixture_entity: Entity,
+}
@@ -8,18 +8,24 @@ use libliquidfun_sys::box2d::ffi::b2Body as ffi_b2Body;
 use libliquidfun_sys::box2d::ffi::b2Fixture as ffi_b2Fixture;
 use libliquidfun_sys::box2d::ffi::{b2ParticleSystem, b2RayCastCallbackImpl, b2Vec2};
 
-use crate::internal::to_Vec2;
+use crate::dynamics::ParticleSystemEntities;
+use crate::internal::{fixture_entities, to_Vec2};
 
 #[derive(Debug)]
 #[allow(non_camel_case_types)]
 pub(crate) struct b2RayCast<T: b2RayCastCallback, F: b2RayCastFilter> {
     callback: T,
     filter: F,
+    particle_systems: ParticleSystemEntities,
 }
 
 impl<T: b2RayCastCallback, F: b2RayCastFilter> b2RayCast<T, F> {
-    pub fn new(callback: T, filter: F) -> Self {
-        Self { callback, filter }
+    pub fn new(callback: T, filter: F, particle_systems: ParticleSystemEntities) -> Self {
+        Self {
+            callback,
+            filter,
+            particle_systems,
+        }
     }
 
     pub fn extract_hits(self) -> T::Result {
@@ -37,20 +43,16 @@ impl<T: b2RayCastCallback, F: b2RayCastFilter> b2RayCastCallbackImpl for b2RayCa
         fraction: f32,
     ) -> f32 {
         unsafe {
+            let (fixture_entity, body_entity) = fixture_entities(fixture);
             let mut ffi_fixture = Pin::new_unchecked(fixture);
-            let user_data = ffi_fixture.as_mut().GetUserData();
-            let pointer_to_entity_bits = user_data.get_unchecked_mut().pointer;
-            let fixture_entity = Entity::from_bits(pointer_to_entity_bits as u64);
-
-            let mut body = Pin::new_unchecked(ffi_fixture.as_mut().GetBody().as_mut().unwrap());
-            let user_data = body.as_mut().GetUserData();
-            let pointer_to_entity_bits = user_data.get_unchecked_mut().pointer;
-            let body_entity = Entity::from_bits(pointer_to_entity_bits as u64);
-
-            if !self
-                .filter
-                .should_use(body_entity, body, fixture_entity, ffi_fixture)
-            {
+            let body = Pin::new_unchecked(ffi_fixture.as_mut().GetBody().as_mut().unwrap());
+
+            if !self.filter.should_use(
+                body_entity,
+                body.as_ref().get_ref(),
+                fixture_entity,
+                ffi_fixture.as_ref().get_ref(),
+            ) {
                 return -1.;
             }
 
@@ -72,11 +74,36 @@ impl<T: b2RayCastCallback, F: b2RayCastFilter> b2RayCastCallbackImpl for b2RayCa
         normal: &b2Vec2,
         fraction: f32,
     ) -> f32 {
-        todo!()
+        let particle_system_entity = self.particle_systems.get(particle_system);
+        if let None = particle_system_entity {
+            return 1.;
+        }
+        let particle_system_entity = particle_system_entity.unwrap();
+
+        if !self
+            .filter
+            .should_use_particle(particle_system_entity, index)
+        {
+            return 1.;
+        }
+
+        return self.callback.report_particle(
+            particle_system_entity,
+            index,
+            &to_Vec2(point),
+            &to_Vec2(normal),
+            fraction,
+        );
     }
 
     fn should_query_particle_system(&mut self, particle_system: *const b2ParticleSystem) -> bool {
-        false
+        let particle_system_entity = self.particle_systems.get(particle_system);
+        if let None = particle_system_entity {
+            return false;
+        }
+
+        self.filter
+            .should_query_particle_system(particle_system_entity.unwrap())
     }
 }
 
@@ -93,6 +120,22 @@ pub trait b2RayCastCallback: Debug {
         fraction: f32,
     ) -> f32;
 
+    /// Called for each particle hit by the ray, for the particle systems selected by the
+    /// filter. Particle systems are ray cast after the fixtures and independently of each
+    /// other, so the reported fractions are not clipped to earlier hits. Return a value of 0 or
+    /// less to terminate the ray cast of the current particle system. Particles are ignored by
+    /// default.
+    fn report_particle(
+        &mut self,
+        _particle_system_entity: Entity,
+        _particle_index: i32,
+        _point: &Vec2,
+        _normal: &Vec2,
+        _fraction: f32,
+    ) -> f32 {
+        1.
+    }
+
     fn into_result(self) -> Self::Result;
 }
 
@@ -106,6 +149,10 @@ impl b2RayCastClosest {
     pub fn new() -> Self {
         Self { result: None }
     }
+
+    fn is_closer(&self, fraction: f32) -> bool {
+        self.result.map_or(true, |hit| fraction < hit.fraction)
+    }
 }
 
 impl b2RayCastCallback for b2RayCastClosest {
@@ -120,14 +167,39 @@ impl b2RayCastCallback for b2RayCastClosest {
         fraction: f32,
     ) -> f32 {
         self.result = Some(b2RayCastHit {
-            body_entity,
-            fixture_entity,
+            target: b2RayCastTarget::Fixture {
+                body_entity,
+                fixture_entity,
+            },
             point: *point,
             normal: *normal,
+            fraction,
         });
         fraction
     }
 
+    fn report_particle(
+        &mut self,
+        particle_system_entity: Entity,
+        particle_index: i32,
+        point: &Vec2,
+        normal: &Vec2,
+        fraction: f32,
+    ) -> f32 {
+        if self.is_closer(fraction) {
+            self.result = Some(b2RayCastHit {
+                target: b2RayCastTarget::Particle {
+                    particle_system_entity,
+                    particle_index,
+                },
+                point: *point,
+                normal: *normal,
+                fraction,
+            });
+        }
+        fraction
+    }
+
     fn into_result(self) -> Self::Result {
         self.result
     }
@@ -154,17 +226,42 @@ impl b2RayCastCallback for b2RayCastAny {
         fixture_entity: Entity,
         point: &Vec2,
         normal: &Vec2,
-        _fraction: f32,
+        fraction: f32,
     ) -> f32 {
         self.result = Some(b2RayCastHit {
-            body_entity,
-            fixture_entity,
+            target: b2RayCastTarget::Fixture {
+                body_entity,
+                fixture_entity,
+            },
             point: *point,
             normal: *normal,
+            fraction,
         });
         0.
     }
 
+    fn report_particle(
+        &mut self,
+        particle_system_entity: Entity,
+        particle_index: i32,
+        point: &Vec2,
+        normal: &Vec2,
+        fraction: f32,
+    ) -> f32 {
+        if self.result.is_none() {
+            self.result = Some(b2RayCastHit {
+                target: b2RayCastTarget::Particle {
+                    particle_system_entity,
+                    particle_index,
+                },
+                point: *point,
+                normal: *normal,
+                fraction,
+            });
+        }
+        0.
+    }
+
     fn into_result(self) -> Self::Result {
         self.result
     }
@@ -191,13 +288,36 @@ impl b2RayCastCallback for b2RayCastAll {
         fixture_entity: Entity,
         point: &Vec2,
         normal: &Vec2,
-        _fraction: f32,
+        fraction: f32,
     ) -> f32 {
         self.result.push(b2RayCastHit {
-            body_entity,
-            fixture_entity,
+            target: b2RayCastTarget::Fixture {
+                body_entity,
+                fixture_entity,
+            },
             point: *point,
             normal: *normal,
+            fraction,
+        });
+        1.
+    }
+
+    fn report_particle(
+        &mut self,
+        particle_system_entity: Entity,
+        particle_index: i32,
+        point: &Vec2,
+        normal: &Vec2,
+        fraction: f32,
+    ) -> f32 {
+        self.result.push(b2RayCastHit {
+            target: b2RayCastTarget::Particle {
+                particle_system_entity,
+                particle_index,
+            },
+            point: *point,
+            normal: *normal,
+            fraction,
         });
         1.
     }
@@ -206,13 +326,45 @@ impl b2RayCastCallback for b2RayCastAll {
         self.result
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum b2RayCastTarget {
+    Fixture {
+        body_entity: Entity,
+        fixture_entity: Entity,
+    },
+    Particle {
+        particle_system_entity: Entity,
+        particle_index: i32,
+    },
+}
+
 #[derive(Debug, Copy, Clone)]
 #[allow(non_camel_case_types)]
 pub struct b2RayCastHit {
-    pub body_entity: Entity,
-    pub fixture_entity: Entity,
+    pub target: b2RayCastTarget,
     pub point: Vec2,
     pub normal: Vec2,
+    pub fraction: f32,
+}
+
+impl b2RayCastHit {
+    /// The body of the hit fixture, or None if a particle was hit.
+    pub fn body_entity(&self) -> Option<Entity> {
+        match self.target {
+            b2RayCastTarget::Fixture { body_entity, .. } => Some(body_entity),
+            b2RayCastTarget::Particle { .. } => None,
+        }
+    }
+
+    /// The hit fixture, or None if a particle was hit.
+    pub fn fixture_entity(&self) -> Option<Entity> {
+        match self.target {
+            b2RayCastTarget::Fixture { fixture_entity, .. } => Some(fixture_entity),
+            b2RayCastTarget::Particle { .. } => None,
+        }
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -220,10 +372,20 @@ pub trait b2RayCastFilter: Debug {
     fn should_use(
         &self,
         body_entity: Entity,
-        body: Pin<&mut ffi_b2Body>,
+        body: &ffi_b2Body,
         fixture_entity: Entity,
-        fixture: Pin<&mut ffi_b2Fixture>,
+        fixture: &ffi_b2Fixture,
     ) -> bool;
+
+    /// Whether the particles of the given particle system should be considered. Particle
+    /// systems are skipped unless a filter opts in, see [`b2ParticleSystemsFilter`].
+    fn should_query_particle_system(&self, _particle_system_entity: Entity) -> bool {
+        false
+    }
+
+    fn should_use_particle(&self, _particle_system_entity: Entity, _particle_index: i32) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Default)]
@@ -234,9 +396,9 @@ impl b2RayCastFilter for b2NoOpFilter {
     fn should_use(
         &self,
         _body_entity: Entity,
-        _body: Pin<&mut ffi_b2Body>,
+        _body: &ffi_b2Body,
         _fixture_entity: Entity,
-        _fixture: Pin<&mut ffi_b2Fixture>,
+        _fixture: &ffi_b2Fixture,
     ) -> bool {
         true
     }
@@ -258,9 +420,9 @@ impl b2RayCastFilter for b2ExcludeBodyFilter {
     fn should_use(
         &self,
         body_entity: Entity,
-        _body: Pin<&mut ffi_b2Body>,
+        _body: &ffi_b2Body,
         _fixture_entity: Entity,
-        _fixture: Pin<&mut ffi_b2Fixture>,
+        _fixture: &ffi_b2Fixture,
     ) -> bool {
         return body_entity != self.excluded_body;
     }
@@ -282,9 +444,9 @@ impl b2RayCastFilter for b2ExcludeBodiesFilter {
     fn should_use(
         &self,
         body_entity: Entity,
-        _body: Pin<&mut ffi_b2Body>,
+        _body: &ffi_b2Body,
         _fixture_entity: Entity,
-        _fixture: Pin<&mut ffi_b2Fixture>,
+        _fixture: &ffi_b2Fixture,
     ) -> bool {
         return !self.excluded_bodies.contains(&body_entity);
     }
@@ -308,11 +470,51 @@ impl b2RayCastFilter for b2CategoryFilter {
     fn should_use(
         &self,
         _body_entity: Entity,
-        _body: Pin<&mut ffi_b2Body>,
+        _body: &ffi_b2Body,
         _fixture_entity: Entity,
-        fixture: Pin<&mut ffi_b2Fixture>,
+        fixture: &ffi_b2Fixture,
     ) -> bool {
         let filter = fixture.GetFilterData();
         return self.allowed_categories & u16::from(filter.categoryBits) != 0;
     }
 }
+
+/// Wraps another filter and additionally considers the particles of the given particle systems.
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct b2ParticleSystemsFilter<F: b2RayCastFilter> {
+    filter: F,
+    particle_systems: HashSet<Entity>,
+}
+
+impl<F: b2RayCastFilter> b2ParticleSystemsFilter<F> {
+    pub fn new(filter: F, particle_systems: &HashSet<Entity>) -> Self {
+        let particle_systems = particle_systems.iter().copied().collect();
+        Self {
+            filter,
+            particle_systems,
+        }
+    }
+}
+
+impl<F: b2RayCastFilter> b2RayCastFilter for b2ParticleSystemsFilter<F> {
+    fn should_use(
+        &self,
+        body_entity: Entity,
+        body: &ffi_b2Body,
+        fixture_entity: Entity,
+        fixture: &ffi_b2Fixture,
+    ) -> bool {
+        self.filter
+            .should_use(body_entity, body, fixture_entity, fixture)
+    }
+
+    fn should_query_particle_system(&self, particle_system_entity: Entity) -> bool {
+        self.particle_systems.contains(&particle_system_entity)
+    }
+
+    fn should_use_particle(&self, particle_system_entity: Entity, particle_index: i32) -> bool {
+        self.filter
+            .should_use_particle(particle_system_entity, particle_index)
+    }
+}
@@ -0,0 +1,480 @@
+use bevy::prelude::*;
+
+use crate::collision::b2Shape;
+
+/// Box2D's `b2_linearSlop`, the distance at which shapes are considered touching.
+const LINEAR_SLOP: f32 = 0.005;
+
+/// Box2D's `b2_polygonRadius`, the skin around polygons, edges and chains.
+const POLYGON_RADIUS: f32 = 2. * LINEAR_SLOP;
+
+/// The distance below which two shapes overlap, as in `b2TestOverlap`.
+pub(crate) const OVERLAP_TOLERANCE: f32 = 10. * f32::EPSILON;
+
+const MAX_ITERATIONS: usize = 30;
+
+#[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct b2ShapeCastHit {
+    pub body_entity: Entity,
+    pub fixture_entity: Entity,
+    pub point: Vec2,
+    pub normal: Vec2,
+
+    /// The fraction of the translation at which the cast shape hits the fixture.
+    pub fraction: f32,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct b2DistanceResult {
+    /// The closest point on the first fixture.
+    pub point_a: Vec2,
+
+    /// The closest point on the second fixture.
+    pub point_b: Vec2,
+
+    pub distance: f32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum b2TimeOfImpactState {
+    Failed,
+    Overlapped,
+    Touching,
+    Separated,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct b2TimeOfImpactResult {
+    pub state: b2TimeOfImpactState,
+
+    /// The time until the fixtures touch, in seconds. Only meaningful if the state is
+    /// [`b2TimeOfImpactState::Touching`].
+    pub time: f32,
+}
+
+/// A position and rotation, like Box2D's `b2Transform`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Placement {
+    position: Vec2,
+    rotation: Vec2,
+}
+
+impl Placement {
+    pub fn new(position: Vec2, angle: f32) -> Self {
+        Self {
+            position,
+            rotation: Vec2::from_angle(angle),
+        }
+    }
+
+    fn apply(&self, point: Vec2) -> Vec2 {
+        self.position + self.rotation.rotate(point)
+    }
+
+    fn translated(&self, translation: Vec2) -> Self {
+        Self {
+            position: self.position + translation,
+            rotation: self.rotation,
+        }
+    }
+}
+
+/// The state of a body moving with constant linear and angular velocity, like Box2D's
+/// `b2Sweep`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Motion {
+    pub center: Vec2,
+    pub local_center: Vec2,
+    pub angle: f32,
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+}
+
+impl Motion {
+    fn placement_at(&self, time: f32) -> Placement {
+        let rotation = Vec2::from_angle(self.angle + self.angular_velocity * time);
+        let center = self.center + self.linear_velocity * time;
+        Placement {
+            position: center - rotation.rotate(self.local_center),
+            rotation,
+        }
+    }
+}
+
+/// A convex point set with a skin radius, like Box2D's `b2DistanceProxy`. Shapes made of
+/// several convex parts, i.e. chains, have one proxy per child.
+#[derive(Debug, Clone)]
+pub(crate) struct ConvexProxy {
+    vertices: Vec<Vec2>,
+    radius: f32,
+}
+
+impl ConvexProxy {
+    pub fn from_shape(shape: &b2Shape) -> Vec<ConvexProxy> {
+        let with_polygon_radius = |vertices: &[Vec2]| ConvexProxy {
+            vertices: vertices.to_vec(),
+            radius: POLYGON_RADIUS,
+        };
+        match shape {
+            b2Shape::Circle { radius, position } => vec![ConvexProxy {
+                vertices: vec![*position],
+                radius: *radius,
+            }],
+            b2Shape::EdgeTwoSided { v1, v2 } => vec![with_polygon_radius(&[*v1, *v2])],
+            b2Shape::Polygon { vertices } if vertices.is_empty() => Vec::new(),
+            b2Shape::Polygon { vertices } => vec![with_polygon_radius(vertices)],
+            b2Shape::Chain { vertices, .. } => {
+                vertices.windows(2).map(with_polygon_radius).collect()
+            }
+            b2Shape::ChainLoop { vertices } => (0..vertices.len())
+                .map(|i| with_polygon_radius(&[vertices[i], vertices[(i + 1) % vertices.len()]]))
+                .collect(),
+        }
+    }
+
+    pub fn transformed(&self, placement: &Placement) -> ConvexProxy {
+        ConvexProxy {
+            vertices: self.vertices.iter().map(|v| placement.apply(*v)).collect(),
+            radius: self.radius,
+        }
+    }
+
+    /// Returns the lower and upper bounds, including the radius.
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let (min, max) = self.vertices.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), v| (min.min(*v), max.max(*v)),
+        );
+        (
+            min - Vec2::splat(self.radius),
+            max + Vec2::splat(self.radius),
+        )
+    }
+
+    /// The largest distance of the proxy's surface from the given point.
+    fn max_extent(&self, point: Vec2) -> f32 {
+        let max_vertex_distance = self
+            .vertices
+            .iter()
+            .map(|v| v.distance(point))
+            .fold(0., f32::max);
+        max_vertex_distance + self.radius
+    }
+
+    /// The edges of the convex hull. A single point or segment is its own only edge.
+    fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let vertex_count = self.vertices.len();
+        let edge_count = if vertex_count <= 2 { 1 } else { vertex_count };
+        (0..edge_count).map(move |i| (self.vertices[i], self.vertices[(i + 1) % vertex_count]))
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        if self.vertices.len() < 3 {
+            return false;
+        }
+
+        let mut winding = 0.;
+        for (a, b) in self.edges() {
+            let side = (b - a).perp_dot(point - a);
+            if side * winding < 0. {
+                return false;
+            }
+            if side != 0. {
+                winding = side;
+            }
+        }
+        true
+    }
+}
+
+/// The closest points between two proxies and the direction from the first to the second.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Separation {
+    pub point_a: Vec2,
+    pub point_b: Vec2,
+
+    /// The distance between the surfaces, or 0 if they overlap.
+    pub distance: f32,
+
+    /// Points from `point_a` towards `point_b`, or is zero if the cores of the proxies overlap.
+    pub normal: Vec2,
+}
+
+impl From<Separation> for b2DistanceResult {
+    fn from(separation: Separation) -> Self {
+        b2DistanceResult {
+            point_a: separation.point_a,
+            point_b: separation.point_b,
+            distance: separation.distance,
+        }
+    }
+}
+
+/// Computes the closest points between two proxies placed in the same frame, taking their radii
+/// into account like `b2Distance` with `useRadii` does.
+pub(crate) fn separation(a: &ConvexProxy, b: &ConvexProxy) -> Separation {
+    let (core_a, core_b) = closest_core_points(a, b);
+    let core_distance = core_a.distance(core_b);
+    let normal = if core_distance > f32::EPSILON {
+        (core_b - core_a) / core_distance
+    } else {
+        Vec2::ZERO
+    };
+
+    let radii = a.radius + b.radius;
+    if core_distance > radii && core_distance > f32::EPSILON {
+        Separation {
+            point_a: core_a + normal * a.radius,
+            point_b: core_b - normal * b.radius,
+            distance: core_distance - radii,
+            normal,
+        }
+    } else {
+        let point = (core_a + core_b) * 0.5;
+        Separation {
+            point_a: point,
+            point_b: point,
+            distance: 0.,
+            normal,
+        }
+    }
+}
+
+/// Finds the closest points of the convex hulls of both proxies, ignoring their radii. Both
+/// points are equal if the hulls overlap.
+fn closest_core_points(a: &ConvexProxy, b: &ConvexProxy) -> (Vec2, Vec2) {
+    if a.contains(b.vertices[0]) {
+        return (b.vertices[0], b.vertices[0]);
+    }
+    if b.contains(a.vertices[0]) {
+        return (a.vertices[0], a.vertices[0]);
+    }
+
+    // Neither hull contains the other, so they are closest (or cross) at a pair of edges
+    let mut closest = (a.vertices[0], b.vertices[0]);
+    let mut closest_distance_squared = f32::MAX;
+    for edge_a in a.edges() {
+        for edge_b in b.edges() {
+            let (point_a, point_b) = closest_points_on_segments(edge_a, edge_b);
+            let distance_squared = point_a.distance_squared(point_b);
+            if distance_squared < closest_distance_squared {
+                closest = (point_a, point_b);
+                closest_distance_squared = distance_squared;
+            }
+        }
+    }
+    closest
+}
+
+fn closest_points_on_segments(a: (Vec2, Vec2), b: (Vec2, Vec2)) -> (Vec2, Vec2) {
+    let direction_a = a.1 - a.0;
+    let direction_b = b.1 - b.0;
+    let denominator = direction_a.perp_dot(direction_b);
+    if denominator != 0. {
+        let offset = b.0 - a.0;
+        let s = offset.perp_dot(direction_b) / denominator;
+        let t = offset.perp_dot(direction_a) / denominator;
+        if (0. ..=1.).contains(&s) && (0. ..=1.).contains(&t) {
+            let intersection = a.0 + direction_a * s;
+            return (intersection, intersection);
+        }
+    }
+
+    // Without an intersection, one of the closest points is an endpoint
+    [
+        (a.0, closest_point_on_segment(a.0, b)),
+        (a.1, closest_point_on_segment(a.1, b)),
+        (closest_point_on_segment(b.0, a), b.0),
+        (closest_point_on_segment(b.1, a), b.1),
+    ]
+    .into_iter()
+    .min_by(|(p0, q0), (p1, q1)| {
+        p0.distance_squared(*q0)
+            .total_cmp(&p1.distance_squared(*q1))
+    })
+    .unwrap()
+}
+
+fn closest_point_on_segment(point: Vec2, segment: (Vec2, Vec2)) -> Vec2 {
+    let direction = segment.1 - segment.0;
+    let length_squared = direction.length_squared();
+    if length_squared == 0. {
+        return segment.0;
+    }
+    let t = ((point - segment.0).dot(direction) / length_squared).clamp(0., 1.);
+    segment.0 + direction * t
+}
+
+/// Sweeps `proxy`, given in the local frame of `placement`, along `translation` against
+/// `target`, which is given in world space. Advances conservatively along the separating normal
+/// until the proxies touch. Returns the point on the target, the target's normal pointing
+/// towards the swept proxy and the fraction of the translation of the hit. A proxy that already
+/// touches the target is hit at a fraction of 0.
+pub(crate) fn shape_cast(
+    target: &ConvexProxy,
+    proxy: &ConvexProxy,
+    placement: &Placement,
+    translation: Vec2,
+) -> Option<(Vec2, Vec2, f32)> {
+    let mut fraction = 0.;
+    for _ in 0..MAX_ITERATIONS {
+        let swept = proxy.transformed(&placement.translated(translation * fraction));
+        let separation = separation(target, &swept);
+        if separation.distance < LINEAR_SLOP {
+            return Some((separation.point_a, separation.normal, fraction));
+        }
+
+        let approach_speed = -translation.dot(separation.normal);
+        if approach_speed <= 0. {
+            return None;
+        }
+
+        fraction += (separation.distance - 0.5 * LINEAR_SLOP) / approach_speed;
+        if fraction > 1. {
+            return None;
+        }
+    }
+    None
+}
+
+/// Computes when two proxies, given in the local frames of their moving bodies, first touch
+/// within `duration` seconds. Uses conservative advancement, bounding how fast the bodies can
+/// approach each other by their relative velocity along the separating normal plus the speed
+/// of their rotating extents.
+pub(crate) fn time_of_impact(
+    a: &ConvexProxy,
+    motion_a: &Motion,
+    b: &ConvexProxy,
+    motion_b: &Motion,
+    duration: f32,
+) -> b2TimeOfImpactResult {
+    let target = LINEAR_SLOP;
+    let tolerance = 0.25 * LINEAR_SLOP;
+    let rotation_speed = motion_a.angular_velocity.abs() * a.max_extent(motion_a.local_center)
+        + motion_b.angular_velocity.abs() * b.max_extent(motion_b.local_center);
+
+    let mut time = 0.;
+    for _ in 0..MAX_ITERATIONS {
+        let separation = separation(
+            &a.transformed(&motion_a.placement_at(time)),
+            &b.transformed(&motion_b.placement_at(time)),
+        );
+        if separation.distance < target + tolerance {
+            let state = if time == 0. && separation.distance < target - tolerance {
+                b2TimeOfImpactState::Overlapped
+            } else {
+                b2TimeOfImpactState::Touching
+            };
+            return b2TimeOfImpactResult { state, time };
+        }
+
+        let approach_speed = (motion_a.linear_velocity - motion_b.linear_velocity)
+            .dot(separation.normal)
+            + rotation_speed;
+        if approach_speed > 0. {
+            time += (separation.distance - target) / approach_speed;
+        }
+        if approach_speed <= 0. || time >= duration {
+            return b2TimeOfImpactResult {
+                state: b2TimeOfImpactState::Separated,
+                time: duration,
+            };
+        }
+    }
+
+    b2TimeOfImpactResult {
+        state: b2TimeOfImpactState::Failed,
+        time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box() -> ConvexProxy {
+        ConvexProxy::from_shape(&b2Shape::create_box(0.5, 0.5)).remove(0)
+    }
+
+    fn circle(radius: f32) -> ConvexProxy {
+        ConvexProxy::from_shape(&b2Shape::Circle {
+            radius,
+            position: Vec2::ZERO,
+        })
+        .remove(0)
+    }
+
+    #[test]
+    fn separation_of_apart_shapes_includes_radii() {
+        let a = unit_box();
+        let b = circle(0.5).transformed(&Placement::new(Vec2::new(3., 0.), 0.));
+        let separation = separation(&a, &b);
+        assert!((separation.distance - (3. - 0.5 - 0.5 - POLYGON_RADIUS)).abs() < 1e-5);
+        assert!(separation
+            .point_a
+            .abs_diff_eq(Vec2::new(0.5 + POLYGON_RADIUS, 0.), 1e-5));
+        assert!(separation.point_b.abs_diff_eq(Vec2::new(2.5, 0.), 1e-5));
+        assert!(separation.normal.abs_diff_eq(Vec2::X, 1e-5));
+    }
+
+    #[test]
+    fn separation_of_overlapping_shapes_is_zero() {
+        let a = unit_box();
+        let b = unit_box().transformed(&Placement::new(Vec2::new(0.5, 0.5), 0.3));
+        assert_eq!(separation(&a, &b).distance, 0.);
+
+        let contained = circle(0.1);
+        assert_eq!(separation(&a, &contained).distance, 0.);
+    }
+
+    #[test]
+    fn shape_cast_stops_at_the_surface() {
+        let target = unit_box();
+        let start = Placement::new(Vec2::new(-5., 0.), 0.);
+        let (point, normal, fraction) =
+            shape_cast(&target, &circle(0.5), &start, Vec2::new(10., 0.)).unwrap();
+
+        let travelled = fraction * 10.;
+        let expected = 5. - 0.5 - 0.5 - POLYGON_RADIUS;
+        assert!((travelled - expected).abs() < LINEAR_SLOP);
+        assert!(point.abs_diff_eq(Vec2::new(-0.5 - POLYGON_RADIUS, 0.), LINEAR_SLOP));
+        assert!(normal.abs_diff_eq(Vec2::NEG_X, 1e-3));
+    }
+
+    #[test]
+    fn shape_cast_misses_shapes_beside_the_path() {
+        let target = unit_box();
+        let start = Placement::new(Vec2::new(-5., 2.), 0.);
+        assert!(shape_cast(&target, &circle(0.5), &start, Vec2::new(10., 0.)).is_none());
+        assert!(shape_cast(&target, &circle(0.5), &start, Vec2::new(-10., 0.)).is_none());
+    }
+
+    #[test]
+    fn time_of_impact_of_approaching_bodies() {
+        let motion_a = Motion {
+            center: Vec2::ZERO,
+            local_center: Vec2::ZERO,
+            angle: 0.,
+            linear_velocity: Vec2::ZERO,
+            angular_velocity: 0.,
+        };
+        let motion_b = Motion {
+            center: Vec2::new(4., 0.),
+            linear_velocity: Vec2::new(-2., 0.),
+            ..motion_a
+        };
+
+        let result = time_of_impact(&unit_box(), &motion_a, &circle(0.5), &motion_b, 2.);
+        assert_eq!(result.state, b2TimeOfImpactState::Touching);
+        let expected = (4. - 0.5 - 0.5 - POLYGON_RADIUS) / 2.;
+        assert!((result.time - expected).abs() < LINEAR_SLOP);
+
+        let result = time_of_impact(&unit_box(), &motion_a, &circle(0.5), &motion_b, 1.);
+        assert_eq!(result.state, b2TimeOfImpactState::Separated);
+        assert_eq!(result.time, 1.);
+    }
+}
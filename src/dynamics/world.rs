@@ -10,9 +10,15 @@ use bevy::prelude::*;
 use libliquidfun_sys::box2d::ffi::{b2ContactListenerWrapper, b2RayCastCallbackWrapper, int32};
 use libliquidfun_sys::box2d::*;
 
+use crate::collision::b2Shape;
+use crate::dynamics::aabb_tree::AabbTree;
+use crate::dynamics::shape_queries::{
+    separation, shape_cast, time_of_impact, ConvexProxy, Motion, Placement, OVERLAP_TOLERANCE,
+};
 use crate::dynamics::{
-    b2Body, b2ContactListener, b2Fixture, b2Joint, b2NoOpFilter, b2RayCast, b2RayCastCallback,
-    b2RayCastFilter, JointPtr,
+    b2Body, b2ContactListener, b2DistanceResult, b2Fixture, b2Joint, b2NoOpFilter, b2OverlapHit,
+    b2PreSolveHook, b2Query, b2QueryAll, b2QueryCallback, b2QueryHit, b2RayCast, b2RayCastCallback,
+    b2RayCastFilter, b2ShapeCastHit, b2TimeOfImpactResult, b2TimeOfImpactState, JointPtr,
 };
 use crate::internal::*;
 use crate::particles::{b2ParticleGroup, b2ParticleSystem};
@@ -37,17 +43,47 @@ impl Default for b2WorldSettings {
     }
 }
 
+/// A joint that was destroyed on the C++ side, along with the bodies it was attached to.
+pub(crate) struct DestroyedJoint {
+    pub joint: Entity,
+    pub bodies: [Entity; 2],
+}
+
+/// Maps the address of each particle system on the C++ side to its entity, so that callbacks
+/// which only receive a particle system pointer can report entities.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ParticleSystemEntities(Rc<RefCell<HashMap<usize, Entity>>>);
+
+impl ParticleSystemEntities {
+    fn insert(&self, particle_system: *const ffi::b2ParticleSystem, entity: Entity) {
+        self.0.borrow_mut().insert(particle_system as usize, entity);
+    }
+
+    pub(crate) fn get(&self, particle_system: *const ffi::b2ParticleSystem) -> Option<Entity> {
+        self.0.borrow().get(&(particle_system as usize)).copied()
+    }
+}
+
 #[allow(non_camel_case_types)]
 pub struct b2World<'a> {
     ffi_world: Pin<Box<ffi::b2World>>,
 
     body_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2Body>>,
     fixture_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2Fixture>>,
+    fixture_proxies: HashMap<Entity, Vec<ConvexProxy>>,
     joint_ptrs: HashMap<Entity, JointPtr<'a>>,
     particle_system_ptrs: HashMap<Entity, Pin<&'a mut ffi::b2ParticleSystem>>,
+    particle_system_entities: ParticleSystemEntities,
 
     body_to_fixtures: HashMap<Entity, HashSet<Entity>>,
     fixture_to_body: HashMap<Entity, Entity>,
+    body_to_joints: HashMap<Entity, HashSet<Entity>>,
+    joint_to_bodies: HashMap<Entity, [Entity; 2]>,
+    joint_to_gear_joints: HashMap<Entity, HashSet<Entity>>,
+    gear_joint_to_joints: HashMap<Entity, [Entity; 2]>,
+
+    fixture_tree: RefCell<Option<AabbTree<Entity>>>,
+    particle_trees: RefCell<HashMap<Entity, AabbTree<i32>>>,
 
     contact_listener: Arc<RefCell<b2ContactListener>>,
     #[allow(dead_code)]
@@ -62,7 +98,8 @@ impl<'a> b2World<'a> {
     pub fn new(gravity: Vec2) -> Self {
         let ffi_gravity = to_b2Vec2(&gravity);
         let mut ffi_world = ffi::b2World::new(&ffi_gravity).within_box();
-        let contact_listener = b2ContactListener::new();
+        let particle_system_entities = ParticleSystemEntities::default();
+        let contact_listener = b2ContactListener::new(particle_system_entities.clone());
         let contact_listener = Arc::new(RefCell::new(contact_listener));
         let ffi_contact_listener = ffi::b2ContactListenerWrapper::new(contact_listener.clone());
 
@@ -80,16 +117,25 @@ impl<'a> b2World<'a> {
             ffi_world,
             body_ptrs: HashMap::new(),
             fixture_ptrs: HashMap::new(),
+            fixture_proxies: HashMap::new(),
             joint_ptrs: HashMap::new(),
             particle_system_ptrs: HashMap::new(),
+            particle_system_entities,
             body_to_fixtures: HashMap::new(),
             fixture_to_body: HashMap::new(),
+            body_to_joints: HashMap::new(),
+            joint_to_bodies: HashMap::new(),
+            joint_to_gear_joints: HashMap::new(),
+            gear_joint_to_joints: HashMap::new(),
+            fixture_tree: RefCell::new(None),
+            particle_trees: RefCell::new(HashMap::new()),
             contact_listener,
             ffi_contact_listener,
         }
     }
 
     pub(crate) fn get_world_ptr(&mut self) -> &mut Pin<Box<ffi::b2World>> {
+        self.invalidate_query_trees();
         &mut self.ffi_world
     }
 
@@ -101,14 +147,25 @@ impl<'a> b2World<'a> {
         &mut self,
         entity: Entity,
     ) -> Option<&mut Pin<&'a mut ffi::b2Body>> {
+        self.invalidate_query_trees();
         self.body_ptrs.get_mut(&entity)
     }
 
+    pub(crate) fn get_fixture_ptr_mut(
+        &mut self,
+        entity: Entity,
+    ) -> Option<&mut Pin<&'a mut ffi::b2Fixture>> {
+        self.invalidate_query_trees();
+        self.fixture_ptrs.get_mut(&entity)
+    }
+
     pub(crate) fn create_body(&mut self, entity: Entity, body: &mut b2Body) {
         let mut b2body_def = ffi::b2BodyDef::new().within_box();
         b2body_def.type_ = body.body_type.into();
         b2body_def.position = to_b2Vec2(&body.position);
         b2body_def.fixedRotation = body.fixed_rotation;
+        b2body_def.bullet = body.bullet;
+        b2body_def.enabled = body.enabled;
         b2body_def.userData.pointer = entity.to_bits() as usize;
 
         unsafe {
@@ -118,13 +175,25 @@ impl<'a> b2World<'a> {
         }
     }
 
-    pub(crate) fn destroy_body_for_entity(&mut self, entity: Entity) {
+    /// Destroys the body and returns the joints that were attached to it. Box2D would destroy
+    /// those along with the body, so they are destroyed explicitly first to keep the joint
+    /// bookkeeping (including gear joints referring to them) consistent.
+    pub(crate) fn destroy_body_for_entity(&mut self, entity: Entity) -> Vec<DestroyedJoint> {
+        self.invalidate_query_trees();
+        let mut destroyed_joints = Vec::new();
+        if let Some(joints) = self.body_to_joints.remove(&entity) {
+            for joint_entity in joints {
+                destroyed_joints.extend(self.destroy_joint_for_entity(joint_entity));
+            }
+        }
+
         let body_ptr = self.body_ptrs.remove(&entity).unwrap();
         let fixtures = self.body_to_fixtures.remove(&entity);
         if let Some(fixtures) = fixtures {
             fixtures.iter().for_each(|f| {
                 self.fixture_to_body.remove(&f);
                 self.fixture_ptrs.remove(&f);
+                self.fixture_proxies.remove(&f);
             });
         }
 
@@ -132,6 +201,8 @@ impl<'a> b2World<'a> {
             let body_ptr = Pin::into_inner_unchecked(body_ptr);
             self.ffi_world.as_mut().DestroyBody(body_ptr);
         }
+
+        destroyed_joints
     }
 
     pub(crate) fn create_fixture(
@@ -142,8 +213,60 @@ impl<'a> b2World<'a> {
         let (fixture_entity, fixture_component) = fixture;
         let (body_entity, body_component) = body;
 
+        self.create_ffi_fixture(fixture_entity, fixture_component, body_entity);
+
+        body_component.fixtures.insert(fixture_entity);
+        let fixtures_for_body = self.body_to_fixtures.entry(body.0).or_default();
+        fixtures_for_body.insert(fixture_entity);
+        self.fixture_to_body.insert(fixture_entity, body_entity);
+    }
+
+    /// Destroys and recreates the FFI fixture of the given entity, e.g. after its shape has
+    /// changed. Contacts that still touch after the next step produce no end and begin events.
+    pub(crate) fn recreate_fixture(&mut self, fixture_entity: Entity, fixture: &b2Fixture) {
+        let fixture_ptr = self.fixture_ptrs.remove(&fixture_entity);
+        if let None = fixture_ptr {
+            return;
+        }
+
+        let fixture_ptr = fixture_ptr.unwrap();
+        let body_entity = self.fixture_to_body[&fixture_entity];
+        let body_ptr = self.body_ptrs.get_mut(&body_entity).unwrap();
+
+        self.contact_listener
+            .borrow_mut()
+            .begin_fixture_recreation(fixture_entity);
+        unsafe {
+            let fixture_ptr = fixture_ptr.get_unchecked_mut();
+            body_ptr.as_mut().DestroyFixture(fixture_ptr);
+        }
+        let touching_bodies = self
+            .contact_listener
+            .borrow_mut()
+            .end_fixture_recreation(fixture_entity);
+
+        self.create_ffi_fixture(fixture_entity, fixture, body_entity);
+
+        // sleeping bodies don't update their contacts, wake them to find out whether the
+        // suspended contacts still touch
+        for body_entity in touching_bodies.iter().chain([&body_entity]) {
+            if let Some(body_ptr) = self.body_ptrs.get_mut(body_entity) {
+                body_ptr.as_mut().SetAwake(true);
+            }
+        }
+    }
+
+    fn create_ffi_fixture(
+        &mut self,
+        fixture_entity: Entity,
+        fixture: &b2Fixture,
+        body_entity: Entity,
+    ) {
+        self.invalidate_query_trees();
+
         let mut body_ptr = self.body_ptrs.get_mut(&body_entity).unwrap().as_mut();
-        let mut b2fixture_def = fixture_component.def().to_ffi();
+        let ffi_shape = fixture.def().shape.to_ffi_owned();
+        let mut b2fixture_def = fixture.def().to_ffi(ffi_shape.as_ref().unwrap());
         let fixture_entity_ptr = fixture_entity.to_bits() as usize;
         b2fixture_def.as_mut().userData.pointer = fixture_entity_ptr;
 
@@ -156,21 +279,96 @@ impl<'a> b2World<'a> {
             let ffi_fixture = Pin::new_unchecked(ffi_fixture);
             self.fixture_ptrs.insert(fixture_entity, ffi_fixture);
         }
-
-        body_component.fixtures.insert(fixture_entity);
-        let fixtures_for_body = self.body_to_fixtures.entry(body.0).or_default();
-        fixtures_for_body.insert(fixture_entity);
-        self.fixture_to_body.insert(fixture_entity, body_entity);
+        self.fixture_proxies.insert(
+            fixture_entity,
+            ConvexProxy::from_shape(&fixture.def().shape),
+        );
     }
 
     pub(crate) fn register_joint(
         &mut self,
         joint: (Entity, &b2Joint, JointPtr<'a>),
-        _body_a: (Entity, &mut b2Body), // TODO
-        _body_b: (Entity, &mut b2Body), // TODO
+        body_a: (Entity, &mut b2Body),
+        body_b: (Entity, &mut b2Body),
+    ) {
+        let (joint_entity, _, joint_ptr) = joint;
+        self.joint_ptrs.insert(joint_entity, joint_ptr);
+
+        body_a.1.joints.insert(joint_entity);
+        body_b.1.joints.insert(joint_entity);
+        for body_entity in [body_a.0, body_b.0] {
+            let joints_for_body = self.body_to_joints.entry(body_entity).or_default();
+            joints_for_body.insert(joint_entity);
+        }
+        self.joint_to_bodies
+            .insert(joint_entity, [body_a.0, body_b.0]);
+    }
+
+    pub(crate) fn register_gear_joint(
+        &mut self,
+        gear_joint_entity: Entity,
+        joint_a: Entity,
+        joint_b: Entity,
     ) {
-        self.joint_ptrs.insert(joint.0, joint.2);
+        for joint_entity in [joint_a, joint_b] {
+            let gear_joints_for_joint = self.joint_to_gear_joints.entry(joint_entity).or_default();
+            gear_joints_for_joint.insert(gear_joint_entity);
+        }
+        self.gear_joint_to_joints
+            .insert(gear_joint_entity, [joint_a, joint_b]);
+    }
+
+    /// Destroys the joint and returns every joint that got destroyed. Box2D requires gear joints
+    /// to be destroyed before the joints they refer to, so those are destroyed first.
+    pub(crate) fn destroy_joint_for_entity(&mut self, entity: Entity) -> Vec<DestroyedJoint> {
+        let mut destroyed_joints = Vec::new();
+        if let Some(gear_joints) = self.joint_to_gear_joints.remove(&entity) {
+            for gear_joint_entity in gear_joints {
+                destroyed_joints.extend(self.destroy_joint_for_entity(gear_joint_entity));
+            }
+        }
+
+        if let Some(joints) = self.gear_joint_to_joints.remove(&entity) {
+            for joint_entity in joints {
+                if let Some(gear_joints) = self.joint_to_gear_joints.get_mut(&joint_entity) {
+                    gear_joints.remove(&entity);
+                }
+            }
+        }
+
+        let bodies = self.joint_to_bodies.remove(&entity);
+        if let Some(bodies) = bodies {
+            for body_entity in bodies {
+                if let Some(joints) = self.body_to_joints.get_mut(&body_entity) {
+                    joints.remove(&entity);
+                }
+            }
+        }
+
+        let joint_ptr = self.joint_ptrs.remove(&entity);
+
+        // The joint might not have been created on the C++ side yet, if it was despawned
+        // during the same frame it was spawned
+        if let None = joint_ptr {
+            return destroyed_joints;
+        }
+
+        let mut joint_ptr = joint_ptr.unwrap();
+        unsafe {
+            self.ffi_world
+                .as_mut()
+                .DestroyJoint(joint_ptr.as_ffi_joint_ptr());
+        }
+
+        if let Some(bodies) = bodies {
+            destroyed_joints.push(DestroyedJoint {
+                joint: entity,
+                bodies,
+            });
+        }
+        destroyed_joints
     }
+
     pub(crate) fn destroy_fixture_for_entity(&mut self, entity: Entity) {
         let fixture_ptr = self.fixture_ptrs.remove(&entity);
 
@@ -181,6 +379,8 @@ impl<'a> b2World<'a> {
         }
 
         let fixture_ptr = fixture_ptr.unwrap();
+        self.fixture_proxies.remove(&entity);
+        self.invalidate_query_trees();
 
         let body_entity = self.fixture_to_body.remove(&entity).unwrap();
         self.body_to_fixtures
@@ -212,6 +412,8 @@ impl<'a> b2World<'a> {
             ffi_particle_system
                 .as_mut()
                 .SetPositionBuffer(positions.as_mut_ptr() as *mut ffi::b2Vec2, capacity);
+            self.particle_system_entities
+                .insert(ffi_particle_system.as_ref().get_ref(), entity);
             self.particle_system_ptrs
                 .insert(entity, ffi_particle_system);
         }
@@ -223,6 +425,7 @@ impl<'a> b2World<'a> {
         _entity: Entity,
         particle_group: &b2ParticleGroup,
     ) {
+        self.invalidate_query_trees();
         let particle_system_ptr = self
             .particle_system_ptrs
             .get_mut(&particle_system_entity)
@@ -238,12 +441,16 @@ impl<'a> b2World<'a> {
         position_iterations: i32,
         particle_iterations: i32,
     ) {
+        self.invalidate_query_trees();
         self.ffi_world.as_mut().Step(
             time_step,
             ffi::int32::from(velocity_iterations),
             ffi::int32::from(position_iterations),
             ffi::int32::from(particle_iterations),
-        )
+        );
+        self.contact_listener
+            .borrow_mut()
+            .end_suspended_fixture_contacts();
     }
 
     pub(crate) fn get_fixtures_attached_to_entity(
@@ -264,6 +471,7 @@ impl<'a> b2World<'a> {
         &mut self,
         particle_system_entity: &Entity,
     ) -> Option<&mut Pin<&'a mut ffi::b2ParticleSystem>> {
+        self.invalidate_query_trees();
         self.particle_system_ptrs.get_mut(particle_system_entity)
     }
 
@@ -275,8 +483,15 @@ impl<'a> b2World<'a> {
         self.contact_listener.clone()
     }
 
+    /// Registers a hook that can disable or modify contacts before they are solved.
+    pub fn add_pre_solve_hook<T: b2PreSolveHook + 'static>(&mut self, hook: T) {
+        self.contact_listener
+            .borrow_mut()
+            .add_pre_solve_hook(Box::new(hook));
+    }
+
     pub fn ray_cast<T: b2RayCastCallback + 'static>(
-        &mut self,
+        &self,
         callback: T,
         start: &Vec2,
         end: &Vec2,
@@ -285,13 +500,14 @@ impl<'a> b2World<'a> {
     }
 
     pub fn ray_cast_with_filter<T: b2RayCastCallback + 'static, F: b2RayCastFilter + 'static>(
-        &mut self,
+        &self,
         callback: T,
         filter: F,
         start: &Vec2,
         end: &Vec2,
     ) -> T::Result {
-        let ray_cast_wrapper = b2RayCast::new(callback, filter);
+        let ray_cast_wrapper =
+            b2RayCast::new(callback, filter, self.particle_system_entities.clone());
         let ray_cast_wrapper = Arc::new(RefCell::new(ray_cast_wrapper));
         let ray_cast_callback_wrapper = b2RayCastCallbackWrapper::new(ray_cast_wrapper.clone());
         unsafe {
@@ -302,7 +518,7 @@ impl<'a> b2World<'a> {
                 .as_mut()
                 .get_unchecked_mut();
             self.ffi_world
-                .as_mut()
+                .as_ref()
                 .RayCast(ffi_callback, &to_b2Vec2(start), &to_b2Vec2(end));
         }
         Arc::try_unwrap(ray_cast_wrapper)
@@ -310,4 +526,314 @@ impl<'a> b2World<'a> {
             .into_inner()
             .extract_hits()
     }
+
+    /// Queries the world for all fixtures whose AABBs overlap the given box, and for the
+    /// particles inside it if the callback asks for them. Fixtures rejected by `filter` are
+    /// skipped.
+    ///
+    /// Fixtures and particles are looked up in bounding volume hierarchies, which are built on
+    /// the first query after the world changed and reused until the next change, e.g. the next
+    /// step.
+    pub fn query_aabb<T: b2QueryCallback, F: b2RayCastFilter>(
+        &self,
+        min: &Vec2,
+        max: &Vec2,
+        callback: T,
+        filter: F,
+    ) -> T::Result {
+        let mut query = b2Query::new(callback, filter, *min, *max);
+        if self.query_fixtures(&mut query) {
+            self.query_particles(&mut query);
+        }
+        query.extract_hits()
+    }
+
+    fn query_fixtures<T: b2QueryCallback, F: b2RayCastFilter>(
+        &self,
+        query: &mut b2Query<T, F>,
+    ) -> bool {
+        let mut fixture_tree = self.fixture_tree.borrow_mut();
+        let fixture_tree = fixture_tree.get_or_insert_with(|| self.build_fixture_tree());
+        fixture_tree.query(query.min(), query.max(), |fixture_entity| {
+            let body_entity = self.fixture_to_body[&fixture_entity];
+            let body = self.body_ptrs[&body_entity].as_ref().get_ref();
+            let fixture = self.fixture_ptrs[&fixture_entity].as_ref().get_ref();
+            query.query_fixture(body_entity, body, fixture_entity, fixture)
+        })
+    }
+
+    fn query_particles<T: b2QueryCallback, F: b2RayCastFilter>(&self, query: &mut b2Query<T, F>) {
+        let mut particle_trees = self.particle_trees.borrow_mut();
+        for (particle_system_entity, particle_system_ptr) in &self.particle_system_ptrs {
+            if !query.should_query_particle_system(*particle_system_entity) {
+                continue;
+            }
+
+            let particle_tree = particle_trees
+                .entry(*particle_system_entity)
+                .or_insert_with(|| build_particle_tree(particle_system_ptr.as_ref().get_ref()));
+            let completed = particle_tree.query(query.min(), query.max(), |particle_index| {
+                query.query_particle(*particle_system_entity, particle_index)
+            });
+            if !completed {
+                return;
+            }
+        }
+    }
+
+    /// Sweeps `shape`, placed at the given position and angle, along `translation` and returns
+    /// all fixtures it hits, ordered by the fraction of the translation at which they are hit.
+    /// Fixtures that already touch the shape at its start position are hit at a fraction of 0.
+    pub fn shape_cast<F: b2RayCastFilter>(
+        &self,
+        shape: &b2Shape,
+        position: Vec2,
+        angle: f32,
+        translation: Vec2,
+        filter: F,
+    ) -> Vec<b2ShapeCastHit> {
+        let placement = Placement::new(position, angle);
+        let proxies = ConvexProxy::from_shape(shape);
+        let (min, max) = proxies
+            .iter()
+            .map(|proxy| proxy.transformed(&placement).bounds())
+            .fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), (proxy_min, proxy_max)| (min.min(proxy_min), max.max(proxy_max)),
+            );
+        let swept_min = min.min(min + translation);
+        let swept_max = max.max(max + translation);
+        let candidates = self.query_aabb(&swept_min, &swept_max, b2QueryAll::new(), filter);
+
+        let mut hits: Vec<b2ShapeCastHit> = candidates
+            .into_iter()
+            .filter_map(|candidate| match candidate {
+                b2QueryHit::Fixture {
+                    body_entity,
+                    fixture_entity,
+                } => {
+                    let targets = self.fixture_proxies_in_world(fixture_entity)?;
+                    targets
+                        .iter()
+                        .flat_map(|target| {
+                            proxies.iter().filter_map(|proxy| {
+                                shape_cast(target, proxy, &placement, translation)
+                            })
+                        })
+                        .min_by(|a, b| a.2.total_cmp(&b.2))
+                        .map(|(point, normal, fraction)| b2ShapeCastHit {
+                            body_entity,
+                            fixture_entity,
+                            point,
+                            normal,
+                            fraction,
+                        })
+                }
+                b2QueryHit::Particle { .. } => None,
+            })
+            .collect();
+        hits.sort_by(|a, b| a.fraction.total_cmp(&b.fraction));
+        hits
+    }
+
+    /// Computes the closest points between two fixtures. Returns None if either fixture has not
+    /// been created in the physics world.
+    pub fn distance(&self, fixture_a: Entity, fixture_b: Entity) -> Option<b2DistanceResult> {
+        let proxies_a = self.fixture_proxies_in_world(fixture_a)?;
+        let proxies_b = self.fixture_proxies_in_world(fixture_b)?;
+        proxies_a
+            .iter()
+            .flat_map(|a| proxies_b.iter().map(move |b| separation(a, b)))
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .map(b2DistanceResult::from)
+    }
+
+    /// Computes when two fixtures will first touch within the next `duration` seconds,
+    /// assuming their bodies keep their current linear and angular velocities. Returns None if
+    /// either fixture has not been created in the physics world.
+    pub fn time_of_impact(
+        &self,
+        fixture_a: Entity,
+        fixture_b: Entity,
+        duration: f32,
+    ) -> Option<b2TimeOfImpactResult> {
+        let proxies_a = self.fixture_proxies.get(&fixture_a)?;
+        let proxies_b = self.fixture_proxies.get(&fixture_b)?;
+        let motion_a = self.fixture_motion(fixture_a)?;
+        let motion_b = self.fixture_motion(fixture_b)?;
+        proxies_a
+            .iter()
+            .flat_map(|a| {
+                proxies_b
+                    .iter()
+                    .map(move |b| time_of_impact(a, &motion_a, b, &motion_b, duration))
+            })
+            .fold(None, |earliest: Option<b2TimeOfImpactResult>, result| {
+                let is_earlier = match (result.state, earliest) {
+                    (_, None) => true,
+                    (b2TimeOfImpactState::Separated, _) => false,
+                    (_, Some(earliest)) => {
+                        earliest.state == b2TimeOfImpactState::Separated
+                            || result.time < earliest.time
+                    }
+                };
+                if is_earlier {
+                    Some(result)
+                } else {
+                    earliest
+                }
+            })
+    }
+
+    /// Returns all fixtures that contain the given world point.
+    pub fn query_point<F: b2RayCastFilter>(&self, point: Vec2, filter: F) -> Vec<b2OverlapHit> {
+        let candidates = self.query_aabb(&point, &point, b2QueryAll::new(), filter);
+        let ffi_point = to_b2Vec2(&point);
+        candidates
+            .into_iter()
+            .filter_map(|candidate| match candidate {
+                b2QueryHit::Fixture {
+                    body_entity,
+                    fixture_entity,
+                } => {
+                    let fixture_ptr = self.fixture_ptrs.get(&fixture_entity)?;
+                    if !fixture_ptr.TestPoint(&ffi_point) {
+                        return None;
+                    }
+                    Some(b2OverlapHit {
+                        body_entity,
+                        fixture_entity,
+                    })
+                }
+                b2QueryHit::Particle { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Returns all fixtures that overlap `shape`, placed at the given position and angle.
+    /// Fixtures count as overlapping when their surfaces touch, like in `b2TestOverlap`.
+    pub fn query_shape<F: b2RayCastFilter>(
+        &self,
+        shape: &b2Shape,
+        position: Vec2,
+        angle: f32,
+        filter: F,
+    ) -> Vec<b2OverlapHit> {
+        let placement = Placement::new(position, angle);
+        let proxies: Vec<ConvexProxy> = ConvexProxy::from_shape(shape)
+            .iter()
+            .map(|proxy| proxy.transformed(&placement))
+            .collect();
+        let (min, max) = proxies.iter().map(ConvexProxy::bounds).fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), (proxy_min, proxy_max)| (min.min(proxy_min), max.max(proxy_max)),
+        );
+        let candidates = self.query_aabb(&min, &max, b2QueryAll::new(), filter);
+
+        candidates
+            .into_iter()
+            .filter_map(|candidate| match candidate {
+                b2QueryHit::Fixture {
+                    body_entity,
+                    fixture_entity,
+                } => {
+                    let targets = self.fixture_proxies_in_world(fixture_entity)?;
+                    let overlaps = targets.iter().any(|target| {
+                        proxies
+                            .iter()
+                            .any(|proxy| separation(target, proxy).distance < OVERLAP_TOLERANCE)
+                    });
+                    if !overlaps {
+                        return None;
+                    }
+                    Some(b2OverlapHit {
+                        body_entity,
+                        fixture_entity,
+                    })
+                }
+                b2QueryHit::Particle { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Returns the convex parts of the fixture's shape, placed at its body's current transform.
+    fn fixture_proxies_in_world(&self, fixture_entity: Entity) -> Option<Vec<ConvexProxy>> {
+        let proxies = self.fixture_proxies.get(&fixture_entity)?;
+        let body = self
+            .body_ptrs
+            .get(self.fixture_to_body.get(&fixture_entity)?)?;
+        let placement = Placement::new(to_Vec2(body.GetPosition()), body.GetAngle());
+        Some(
+            proxies
+                .iter()
+                .map(|proxy| proxy.transformed(&placement))
+                .collect(),
+        )
+    }
+
+    fn fixture_motion(&self, fixture_entity: Entity) -> Option<Motion> {
+        let body = self
+            .body_ptrs
+            .get(self.fixture_to_body.get(&fixture_entity)?)?;
+        Some(Motion {
+            center: to_Vec2(body.GetWorldCenter()),
+            local_center: to_Vec2(body.GetLocalCenter()),
+            angle: body.GetAngle(),
+            linear_velocity: to_Vec2(body.GetLinearVelocity()),
+            angular_velocity: body.GetAngularVelocity(),
+        })
+    }
+
+    /// Builds a tree with one leaf per fixture, bounding all of its child AABBs. Fixtures of
+    /// disabled bodies are left out, like Box2D does, since they have no up-to-date AABBs.
+    fn build_fixture_tree(&self) -> AabbTree<Entity> {
+        let leaves = self
+            .fixture_ptrs
+            .iter()
+            .filter_map(|(fixture_entity, fixture_ptr)| {
+                let body_entity = self.fixture_to_body[fixture_entity];
+                if !self.body_ptrs[&body_entity].IsEnabled() {
+                    return None;
+                }
+
+                let fixture = fixture_ptr.as_ref().get_ref();
+                let child_count =
+                    unsafe { i32::from(fixture.GetShape1().as_ref().unwrap().GetChildCount()) };
+                let (min, max) = (0..child_count)
+                    .map(|child_index| fixture.GetAABB(int32::from(child_index)))
+                    .fold(
+                        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                        |(min, max), aabb| {
+                            (
+                                min.min(to_Vec2(&aabb.lowerBound)),
+                                max.max(to_Vec2(&aabb.upperBound)),
+                            )
+                        },
+                    );
+                Some((min, max, *fixture_entity))
+            })
+            .collect();
+        AabbTree::new(leaves)
+    }
+
+    /// Drops the cached query trees, so that the next query rebuilds them. Called by everything
+    /// that can add, remove or move fixtures or particles.
+    fn invalidate_query_trees(&mut self) {
+        *self.fixture_tree.get_mut() = None;
+        self.particle_trees.get_mut().clear();
+    }
+}
+
+fn build_particle_tree(particle_system: &ffi::b2ParticleSystem) -> AabbTree<i32> {
+    let particle_count = i32::from(particle_system.GetParticleCount());
+    let leaves = (0..particle_count)
+        .map(|index| {
+            let position = Vec2::new(
+                particle_system.GetParticlePositionX(int32::from(index)),
+                particle_system.GetParticlePositionY(int32::from(index)),
+            );
+            (position, position, index)
+        })
+        .collect();
+    AabbTree::new(leaves)
 }
@@ -1,4 +1,8 @@
+use std::pin::Pin;
+
 use bevy::math::Vec2;
+use bevy::prelude::Entity;
+use libliquidfun_sys::box2d::ffi;
 use libliquidfun_sys::box2d::ffi::b2Vec2;
 
 #[allow(non_snake_case)]
@@ -10,3 +14,60 @@ pub(crate) fn to_b2Vec2(vec2: &Vec2) -> b2Vec2 {
 pub(crate) fn to_Vec2(b2vec2: &b2Vec2) -> Vec2 {
     unsafe { std::mem::transmute_copy(b2vec2) }
 }
+
+/// Mirrors the layout of `b2ContactImpulse`, which libliquidfun-sys only exposes as an opaque
+/// type without field access.
+#[repr(C)]
+pub(crate) struct ContactImpulse {
+    pub normal_impulses: [f32; 2],
+    pub tangent_impulses: [f32; 2],
+    pub count: i32,
+}
+
+const _: () =
+    assert!(std::mem::size_of::<ffi::b2ContactImpulse>() == std::mem::size_of::<ContactImpulse>());
+
+pub(crate) fn read_contact_impulse(impulse: &ffi::b2ContactImpulse) -> ContactImpulse {
+    unsafe { std::mem::transmute_copy(impulse) }
+}
+
+/// Mirrors the layout of `b2Manifold`, which libliquidfun-sys only exposes as an opaque type
+/// without field access.
+#[repr(C)]
+#[allow(dead_code)]
+struct Manifold {
+    points: [ManifoldPoint; 2],
+    local_normal: [f32; 2],
+    local_point: [f32; 2],
+    manifold_type: i32,
+    point_count: i32,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct ManifoldPoint {
+    local_point: [f32; 2],
+    normal_impulse: f32,
+    tangent_impulse: f32,
+    id: u32,
+}
+
+const _: () = assert!(std::mem::size_of::<ffi::b2Manifold>() == std::mem::size_of::<Manifold>());
+
+pub(crate) fn manifold_point_count(manifold: &ffi::b2Manifold) -> usize {
+    let manifold: Manifold = unsafe { std::mem::transmute_copy(manifold) };
+    manifold.point_count as usize
+}
+
+/// Returns the entities of the fixture and of the body it is attached to.
+pub(crate) fn fixture_entities(fixture: &mut ffi::b2Fixture) -> (Entity, Entity) {
+    unsafe {
+        let mut fixture = Pin::new_unchecked(fixture);
+        let mut body = Pin::new_unchecked(fixture.as_mut().GetBody().as_mut().unwrap());
+        let fixture_entity =
+            Entity::from_bits(fixture.as_mut().GetUserData().get_unchecked_mut().pointer as u64);
+        let body_entity =
+            Entity::from_bits(body.as_mut().GetUserData().get_unchecked_mut().pointer as u64);
+        (fixture_entity, body_entity)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod collision;
+pub mod picking;
 pub mod plugins;
 pub mod utils;
 
@@ -9,11 +10,37 @@ pub mod dynamics {
     mod joints {
         mod joint;
         pub use joint::*;
+        mod joint_events;
+        pub use joint_events::*;
         mod revolute_joint;
         pub use revolute_joint::*;
 
         mod prismatic_joint;
         pub use prismatic_joint::*;
+
+        mod distance_joint;
+        pub use distance_joint::*;
+
+        mod weld_joint;
+        pub use weld_joint::*;
+
+        mod wheel_joint;
+        pub use wheel_joint::*;
+
+        mod mouse_joint;
+        pub use mouse_joint::*;
+
+        mod pulley_joint;
+        pub use pulley_joint::*;
+
+        mod gear_joint;
+        pub use gear_joint::*;
+
+        mod friction_joint;
+        pub use friction_joint::*;
+
+        mod motor_joint;
+        pub use motor_joint::*;
     }
     mod contacts {
         mod contact_events;
@@ -22,16 +49,25 @@ pub mod dynamics {
         pub use contact_listener::*;
         mod contacts;
         pub use contacts::*;
+        mod pre_solve;
+        pub use pre_solve::*;
     }
+    mod aabb_tree;
     mod fixture;
+    mod physics_query;
+    mod query;
     mod ray_cast;
+    mod shape_queries;
     mod world;
 
     pub use body::*;
     pub use contacts::*;
     pub use fixture::*;
     pub use joints::*;
+    pub use physics_query::*;
+    pub use query::*;
     pub use ray_cast::*;
+    pub use shape_queries::*;
     pub use world::*;
 }
 
@@ -6,7 +6,7 @@ use bevy::prelude::{Component, Entity};
 use libliquidfun_sys::box2d::ffi;
 use libliquidfun_sys::box2d::ffi::int32;
 
-use crate::dynamics::{b2ParticleBodyContact, b2World};
+use crate::dynamics::{b2ParticleBodyContact, b2ParticleContact, b2World};
 use crate::particles::b2ParticleDef;
 
 #[allow(non_camel_case_types)]
@@ -172,6 +172,7 @@ impl b2ParticleSystem {
 #[derive(Component, Debug, Default)]
 pub struct b2ParticleSystemContacts {
     body_contacts: Vec<b2ParticleBodyContact>,
+    particle_contacts: Vec<b2ParticleContact>,
 }
 
 impl b2ParticleSystemContacts {
@@ -182,4 +183,12 @@ impl b2ParticleSystemContacts {
     pub(crate) fn body_contacts_mut(&mut self) -> &mut Vec<b2ParticleBodyContact> {
         &mut self.body_contacts
     }
+
+    pub fn particle_contacts(&self) -> &Vec<b2ParticleContact> {
+        &self.particle_contacts
+    }
+
+    pub(crate) fn particle_contacts_mut(&mut self) -> &mut Vec<b2ParticleContact> {
+        &mut self.particle_contacts
+    }
 }
@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use libliquidfun_sys::box2d::ffi;
+
+use crate::dynamics::{b2BodyType, b2RayCastFilter, b2World};
+
+#[derive(Resource, Debug, Clone)]
+pub struct MousePickingSettings {
+    /// The mouse button that grabs a body while held down.
+    pub button: MouseButton,
+
+    /// The maximum force of the mouse joint, as a multiple of the picked body's mass.
+    pub max_force_per_mass: f32,
+
+    /// The frequency of the mouse joint spring in Hertz.
+    pub frequency_hertz: f32,
+
+    /// The damping ratio of the mouse joint spring. 1 is critical damping.
+    pub damping_ratio: f32,
+}
+
+impl Default for MousePickingSettings {
+    fn default() -> Self {
+        Self {
+            button: MouseButton::Left,
+            max_force_per_mass: 1000.,
+            frequency_hertz: 5.,
+            damping_ratio: 0.7,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct MousePickingState {
+    pub(crate) anchor_body: Option<Entity>,
+    pub(crate) joint: Option<Entity>,
+    pub(crate) picked_body: Option<Entity>,
+}
+
+impl MousePickingState {
+    pub fn joint(&self) -> Option<Entity> {
+        self.joint
+    }
+
+    pub fn picked_body(&self) -> Option<Entity> {
+        self.picked_body
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MousePickingAction {
+    None,
+    Grab,
+    Drag,
+    Release,
+}
+
+/// Maps the state of the picking button to what should happen to the mouse joint this frame.
+pub fn mouse_picking_action(
+    button_pressed: bool,
+    button_just_pressed: bool,
+    is_grabbing: bool,
+) -> MousePickingAction {
+    match (is_grabbing, button_pressed) {
+        (false, true) if button_just_pressed => MousePickingAction::Grab,
+        (true, true) => MousePickingAction::Drag,
+        (true, false) => MousePickingAction::Release,
+        _ => MousePickingAction::None,
+    }
+}
+
+/// Converts a cursor position in logical window pixels to a point in world space.
+pub fn cursor_to_world(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    cursor_position: Vec2,
+) -> Option<Vec2> {
+    camera.viewport_to_world_2d(camera_transform, cursor_position)
+}
+
+/// Returns a dynamic body that has a fixture containing the given world point.
+///
+/// Uses [`b2World::query_point`], so picking agrees with what Box2D considers to be inside a
+/// fixture.
+pub fn find_pickable_body(b2_world: &b2World, point: Vec2) -> Option<Entity> {
+    b2_world
+        .query_point(point, DynamicBodiesFilter)
+        .first()
+        .map(|hit| hit.body_entity)
+}
+
+#[derive(Debug)]
+struct DynamicBodiesFilter;
+
+impl b2RayCastFilter for DynamicBodiesFilter {
+    fn should_use(
+        &self,
+        _body_entity: Entity,
+        body: &ffi::b2Body,
+        _fixture_entity: Entity,
+        _fixture: &ffi::b2Fixture,
+    ) -> bool {
+        matches!(b2BodyType::from(body.GetType()), b2BodyType::Dynamic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::collision::b2Shape;
+    use crate::dynamics::{b2Body, b2BodyDef, b2Fixture, b2FixtureDef};
+
+    #[test]
+    fn pressing_the_button_grabs() {
+        assert_eq!(
+            mouse_picking_action(true, true, false),
+            MousePickingAction::Grab
+        );
+    }
+
+    #[test]
+    fn holding_the_button_without_a_grab_does_nothing() {
+        assert_eq!(
+            mouse_picking_action(true, false, false),
+            MousePickingAction::None
+        );
+    }
+
+    #[test]
+    fn holding_the_button_drags() {
+        assert_eq!(
+            mouse_picking_action(true, false, true),
+            MousePickingAction::Drag
+        );
+        assert_eq!(
+            mouse_picking_action(true, true, true),
+            MousePickingAction::Drag
+        );
+    }
+
+    #[test]
+    fn releasing_the_button_releases() {
+        assert_eq!(
+            mouse_picking_action(false, false, true),
+            MousePickingAction::Release
+        );
+    }
+
+    #[test]
+    fn idle_button_does_nothing() {
+        assert_eq!(
+            mouse_picking_action(false, false, false),
+            MousePickingAction::None
+        );
+    }
+
+    fn spawn_body(
+        b2_world: &mut b2World,
+        entity_index: u32,
+        body_def: &b2BodyDef,
+        shape: b2Shape,
+    ) -> Entity {
+        let body_entity = Entity::from_raw(entity_index);
+        let fixture_entity = Entity::from_raw(entity_index + 1);
+        let mut body = b2Body::new(body_def);
+        b2_world.create_body(body_entity, &mut body);
+        // create_body only applies the position, sync to also apply the angle
+        body.sync_to_world(body_entity, b2_world);
+
+        let mut fixture = b2Fixture::new(body_entity, &b2FixtureDef::new(shape, 1.));
+        b2_world.create_fixture((fixture_entity, &mut fixture), (body_entity, &mut body));
+        body_entity
+    }
+
+    fn dynamic_body_def(position: Vec2, angle: f32) -> b2BodyDef {
+        b2BodyDef {
+            body_type: b2BodyType::Dynamic,
+            position,
+            angle,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn picks_a_circle() {
+        let mut b2_world = b2World::new(Vec2::ZERO);
+        let shape = b2Shape::Circle {
+            radius: 1.,
+            position: Vec2::new(1., 0.),
+        };
+        let body = spawn_body(
+            &mut b2_world,
+            0,
+            &dynamic_body_def(Vec2::new(5., 5.), 0.),
+            shape,
+        );
+
+        assert_eq!(
+            find_pickable_body(&b2_world, Vec2::new(6.5, 5.)),
+            Some(body)
+        );
+        assert_eq!(find_pickable_body(&b2_world, Vec2::new(4.5, 5.)), None);
+    }
+
+    #[test]
+    fn picks_a_circle_on_a_rotated_body() {
+        let mut b2_world = b2World::new(Vec2::ZERO);
+        let shape = b2Shape::Circle {
+            radius: 1.,
+            position: Vec2::new(2., 0.),
+        };
+        let body = spawn_body(
+            &mut b2_world,
+            0,
+            &dynamic_body_def(Vec2::ZERO, FRAC_PI_2),
+            shape,
+        );
+
+        assert_eq!(find_pickable_body(&b2_world, Vec2::new(0., 2.)), Some(body));
+        assert_eq!(find_pickable_body(&b2_world, Vec2::new(2., 0.)), None);
+    }
+
+    #[test]
+    fn picks_a_polygon_on_a_rotated_body() {
+        let mut b2_world = b2World::new(Vec2::ZERO);
+        let shape = b2Shape::create_box_with_offset(2., 0.5, Vec2::new(3., 0.));
+        let body = spawn_body(
+            &mut b2_world,
+            0,
+            &dynamic_body_def(Vec2::new(1., 1.), FRAC_PI_2),
+            shape,
+        );
+
+        assert_eq!(find_pickable_body(&b2_world, Vec2::new(1., 5.)), Some(body));
+        assert_eq!(
+            find_pickable_body(&b2_world, Vec2::new(1.4, 2.2)),
+            Some(body)
+        );
+        assert_eq!(find_pickable_body(&b2_world, Vec2::new(4., 1.)), None);
+        assert_eq!(find_pickable_body(&b2_world, Vec2::new(2., 4.)), None);
+    }
+
+    #[test]
+    fn does_not_pick_static_bodies() {
+        let mut b2_world = b2World::new(Vec2::ZERO);
+        spawn_body(
+            &mut b2_world,
+            0,
+            &b2BodyDef::default(),
+            b2Shape::create_box(1., 1.),
+        );
+
+        assert_eq!(find_pickable_body(&b2_world, Vec2::ZERO), None);
+    }
+
+    #[test]
+    fn picks_the_dynamic_body_above_a_static_one() {
+        let mut b2_world = b2World::new(Vec2::ZERO);
+        spawn_body(
+            &mut b2_world,
+            0,
+            &b2BodyDef::default(),
+            b2Shape::create_box(5., 5.),
+        );
+        let body = spawn_body(
+            &mut b2_world,
+            2,
+            &dynamic_body_def(Vec2::new(1., 1.), 0.),
+            b2Shape::create_box(1., 1.),
+        );
+
+        assert_eq!(
+            find_pickable_body(&b2_world, Vec2::new(1.5, 0.5)),
+            Some(body)
+        );
+    }
+}
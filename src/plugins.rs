@@ -1,17 +1,31 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 use bevy::transform::TransformSystem;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
 
 use libliquidfun_sys::box2d::ffi::int32;
 
 use crate::collision::b2Shape;
 use crate::dynamics::{
-    b2BeginContactEvent, b2Body, b2EndContactEvent, b2Fixture, b2Joint, b2ParticleBodyContact,
-    b2ParticleContacts, b2PrismaticJoint, b2RevoluteJoint, b2World, b2WorldSettings, ExternalForce,
-    ExternalTorque, GravityScale, JointPtr,
+    b2BeginContactEvent, b2Body, b2BodyBundle, b2BodyContact, b2Breakable, b2ContactImpulseEvent,
+    b2ContactImpulseThreshold, b2Contacts, b2DistanceJoint, b2EndContactEvent, b2Fixture,
+    b2FrictionJoint, b2GearJoint, b2Joint, b2JointBrokenEvent, b2MotorJoint, b2MouseJoint,
+    b2MouseJointDef, b2ParticleBodyContact, b2ParticleBodyContactBegin, b2ParticleBodyContactEnd,
+    b2ParticleContact, b2ParticleContacts, b2ParticleParticleContactBegin,
+    b2ParticleParticleContactEnd, b2PrismaticJoint, b2PulleyJoint, b2RevoluteJoint,
+    b2SensorEnterEvent, b2SensorExitEvent, b2SensorOverlaps, b2WeldJoint, b2WheelJoint, b2World,
+    b2WorldSettings, linear_stiffness, CreateMouseJoint, DestroyedJoint, ExternalForce,
+    ExternalTorque, GravityScale, JointPtr, OneWayPlatform,
 };
 use crate::internal::to_b2Vec2;
 use crate::particles::{b2ParticleGroup, b2ParticleSystem, b2ParticleSystemContacts};
-use crate::utils::{DebugDrawFixtures, DebugDrawParticleSystem};
+use crate::picking::{
+    cursor_to_world, find_pickable_body, mouse_picking_action, MousePickingAction,
+    MousePickingSettings, MousePickingState,
+};
+use crate::utils::{DebugDrawFixtures, DebugDrawJoint, DebugDrawParticleSystem};
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub enum LiquidFunSet {
@@ -55,23 +69,58 @@ impl Plugin for LiquidFunPlugin {
                 (
                     clear_events::<b2BeginContactEvent>,
                     clear_events::<b2EndContactEvent>,
+                    clear_events::<b2ContactImpulseEvent>,
+                    clear_events::<b2ParticleBodyContactBegin>,
+                    clear_events::<b2ParticleBodyContactEnd>,
+                    clear_events::<b2ParticleParticleContactBegin>,
+                    clear_events::<b2ParticleParticleContactEnd>,
+                    clear_events::<b2SensorEnterEvent>,
+                    clear_events::<b2SensorExitEvent>,
+                    clear_events::<b2JointBrokenEvent>,
                 )
                     .in_set(LiquidFunSet::ClearEvents),
                 (
                     create_bodies,
                     create_fixtures,
-                    create_revolute_joints,
-                    create_prismatic_joints,
+                    sync_sensor_overlaps_components,
+                    (
+                        create_revolute_joints,
+                        create_prismatic_joints,
+                        create_distance_joints,
+                        create_weld_joints,
+                        create_wheel_joints,
+                        create_mouse_joints,
+                        create_pulley_joints,
+                        create_friction_joints,
+                        create_motor_joints,
+                        create_gear_joints,
+                    )
+                        .chain(),
                     create_particle_systems,
                     create_particle_groups,
                     create_queued_particles,
+                    destroy_removed_joints,
                     destroy_removed_fixtures,
                     destroy_removed_bodies,
                     destroy_queued_particles,
                     apply_deferred,
                     sync_bodies_to_world,
-                    sync_revolute_joints_to_world,
-                    sync_prismatic_joints_to_world,
+                    sync_fixtures_to_world,
+                    sync_contact_impulse_thresholds,
+                    (
+                        sync_revolute_joints_to_world,
+                        sync_prismatic_joints_to_world,
+                        sync_distance_joints_to_world,
+                        sync_weld_joints_to_world,
+                        sync_wheel_joints_to_world,
+                        sync_mouse_joints_to_world,
+                        sync_gear_joints_to_world,
+                        sync_friction_joints_to_world,
+                        sync_motor_joints_to_world,
+                    )
+                        .chain(),
+                    sync_one_way_platforms,
+                    sync_bodies_with_contacts,
                 )
                     .chain()
                     .in_set(LiquidFunSet::SyncToPhysicsWorld),
@@ -81,8 +130,20 @@ impl Plugin for LiquidFunPlugin {
                 (step_physics).in_set(LiquidFunSet::Step),
                 (
                     sync_bodies_from_world,
+                    (
+                        sync_joints_from_world,
+                        sync_revolute_joints_from_world,
+                        sync_prismatic_joints_from_world,
+                        sync_distance_joints_from_world,
+                        sync_wheel_joints_from_world,
+                        sync_pulley_joints_from_world,
+                    )
+                        .chain(),
+                    break_joints,
                     sync_particle_systems_from_world,
                     update_transforms,
+                    update_sensor_overlaps,
+                    update_body_contacts,
                     send_contact_events,
                     copy_particle_system_contacts,
                     update_particle_body_contacts_components,
@@ -92,7 +153,15 @@ impl Plugin for LiquidFunPlugin {
             ),
         )
         .init_resource::<Events<b2BeginContactEvent>>()
-        .init_resource::<Events<b2EndContactEvent>>();
+        .init_resource::<Events<b2EndContactEvent>>()
+        .init_resource::<Events<b2ContactImpulseEvent>>()
+        .init_resource::<Events<b2ParticleBodyContactBegin>>()
+        .init_resource::<Events<b2ParticleBodyContactEnd>>()
+        .init_resource::<Events<b2ParticleParticleContactBegin>>()
+        .init_resource::<Events<b2ParticleParticleContactEnd>>()
+        .init_resource::<Events<b2SensorEnterEvent>>()
+        .init_resource::<Events<b2SensorExitEvent>>()
+        .init_resource::<Events<b2JointBrokenEvent>>();
     }
 }
 
@@ -154,6 +223,32 @@ fn create_fixtures(
     }
 }
 
+fn sync_sensor_overlaps_components(
+    changed: Query<(Entity, &b2Fixture, Option<&b2SensorOverlaps>), Changed<b2Fixture>>,
+    mut sensor_exit_events: EventWriter<b2SensorExitEvent>,
+    mut commands: Commands,
+) {
+    for (entity, fixture, sensor_overlaps) in changed.iter() {
+        match (fixture.def().is_sensor, sensor_overlaps) {
+            (true, None) => {
+                commands.entity(entity).insert(b2SensorOverlaps::default());
+            }
+            (false, Some(sensor_overlaps)) => {
+                // the fixture stopped being a sensor, so nothing overlaps it anymore
+                for (fixture, body) in sensor_overlaps.overlaps().iter() {
+                    sensor_exit_events.send(b2SensorExitEvent {
+                        sensor: entity,
+                        fixture: *fixture,
+                        body: *body,
+                    });
+                }
+                commands.entity(entity).remove::<b2SensorOverlaps>();
+            }
+            _ => {}
+        }
+    }
+}
+
 fn create_revolute_joints(
     mut b2_world: NonSendMut<b2World>,
     mut added: Query<(Entity, &b2Joint, &b2RevoluteJoint), Added<b2RevoluteJoint>>,
@@ -199,6 +294,196 @@ fn create_prismatic_joints(
         );
     }
 }
+
+fn create_distance_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2DistanceJoint), Added<b2DistanceJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, distance_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = distance_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
+fn create_weld_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2WeldJoint), Added<b2WeldJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, weld_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = weld_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
+fn create_wheel_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2WheelJoint), Added<b2WheelJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, wheel_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = wheel_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
+fn create_mouse_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2MouseJoint), Added<b2MouseJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, mouse_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = mouse_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
+fn create_pulley_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2PulleyJoint), Added<b2PulleyJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, pulley_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = pulley_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
+fn create_gear_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2GearJoint), Added<b2GearJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, gear_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = gear_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        if let None = joint_ptr {
+            continue;
+        }
+        let joint_ptr = joint_ptr.unwrap();
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+        b2_world.register_gear_joint(joint_entity, gear_joint.joint_a(), gear_joint.joint_b());
+    }
+}
+
+fn create_friction_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2FrictionJoint), Added<b2FrictionJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, friction_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = friction_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
+fn create_motor_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut added: Query<(Entity, &b2Joint, &b2MotorJoint), Added<b2MotorJoint>>,
+    mut bodies: Query<(Entity, &mut b2Body)>,
+) {
+    for (joint_entity, joint, motor_joint) in added.iter_mut() {
+        let [mut body_a, mut body_b] = bodies
+            .get_many_mut([*joint.body_a(), *joint.body_b()])
+            .unwrap();
+        let joint_ptr = motor_joint.create_ffi_joint(
+            &mut b2_world,
+            body_a.0,
+            body_b.0,
+            joint.collide_connected(),
+        );
+        b2_world.register_joint(
+            (joint_entity, &joint, joint_ptr),
+            (body_a.0, &mut body_a.1),
+            (body_b.0, &mut body_b.1),
+        );
+    }
+}
+
 fn create_particle_systems(
     mut commands: Commands,
     mut b2_world: NonSendMut<b2World>,
@@ -238,6 +523,7 @@ fn create_queued_particles(
 fn destroy_removed_bodies(
     mut b2_world: NonSendMut<b2World>,
     mut removed: RemovedComponents<b2Body>,
+    mut bodies: Query<&mut b2Body>,
     mut commands: Commands,
 ) {
     for entity in removed.read() {
@@ -248,7 +534,13 @@ fn destroy_removed_bodies(
             });
         }
 
-        b2_world.destroy_body_for_entity(entity);
+        let destroyed_joints = b2_world.destroy_body_for_entity(entity);
+        for destroyed_joint in destroyed_joints {
+            forget_destroyed_joint(&destroyed_joint, &mut bodies);
+            if let Some(joint_commands) = commands.get_entity(destroyed_joint.joint) {
+                joint_commands.despawn_recursive();
+            }
+        }
     }
 }
 
@@ -262,6 +554,35 @@ fn destroy_queued_particles(
     }
 }
 
+fn destroy_removed_joints(
+    mut b2_world: NonSendMut<b2World>,
+    mut removed: RemovedComponents<b2Joint>,
+    mut bodies: Query<&mut b2Body>,
+    mut commands: Commands,
+) {
+    for entity in removed.read() {
+        let destroyed_joints = b2_world.destroy_joint_for_entity(entity);
+        for destroyed_joint in destroyed_joints {
+            forget_destroyed_joint(&destroyed_joint, &mut bodies);
+
+            // gear joints referring to the removed joint got destroyed along with it
+            if destroyed_joint.joint != entity {
+                if let Some(mut joint_commands) = commands.get_entity(destroyed_joint.joint) {
+                    joint_commands.despawn();
+                }
+            }
+        }
+    }
+}
+
+fn forget_destroyed_joint(destroyed_joint: &DestroyedJoint, bodies: &mut Query<&mut b2Body>) {
+    for body_entity in destroyed_joint.bodies {
+        if let Ok(mut body) = bodies.get_mut(body_entity) {
+            body.joints.remove(&destroyed_joint.joint);
+        }
+    }
+}
+
 fn destroy_removed_fixtures(
     mut b2_world: NonSendMut<b2World>,
     mut removed: RemovedComponents<b2Fixture>,
@@ -279,6 +600,23 @@ fn sync_bodies_to_world(
     }
 }
 
+fn sync_fixtures_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    mut fixtures: Query<(Entity, &mut b2Fixture), Changed<b2Fixture>>,
+) {
+    for (entity, mut fixture) in fixtures.iter_mut() {
+        if fixture.shape_changed() {
+            b2_world.recreate_fixture(entity, &fixture);
+            fixture.bypass_change_detection().mark_shape_created();
+            continue;
+        }
+
+        if let Some(fixture_ptr) = b2_world.get_fixture_ptr_mut(entity) {
+            fixture.sync_to_world(fixture_ptr.as_mut());
+        }
+    }
+}
+
 fn sync_revolute_joints_to_world(
     mut b2_world: NonSendMut<b2World>,
     joints: Query<(Entity, &b2RevoluteJoint), Changed<b2RevoluteJoint>>,
@@ -303,6 +641,138 @@ fn sync_prismatic_joints_to_world(
     }
 }
 
+fn sync_distance_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2DistanceJoint), Changed<b2DistanceJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Distance(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_weld_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2WeldJoint), Changed<b2WeldJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Weld(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_wheel_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2WheelJoint), Changed<b2WheelJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Wheel(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_mouse_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2MouseJoint), Changed<b2MouseJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Mouse(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_gear_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2GearJoint), Changed<b2GearJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Gear(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_friction_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2FrictionJoint), Changed<b2FrictionJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Friction(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_motor_joints_to_world(
+    mut b2_world: NonSendMut<b2World>,
+    joints: Query<(Entity, &b2MotorJoint), Changed<b2MotorJoint>>,
+) {
+    for (entity, joint) in joints.iter() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity).unwrap();
+        if let JointPtr::Motor(joint_ptr) = joint_ptr {
+            joint.sync_to_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_contact_impulse_thresholds(
+    b2_world: NonSend<b2World>,
+    thresholds: Query<(Entity, &b2ContactImpulseThreshold), Changed<b2ContactImpulseThreshold>>,
+    mut removed: RemovedComponents<b2ContactImpulseThreshold>,
+) {
+    let contact_listener = b2_world.contact_listener();
+    let mut contact_listener = contact_listener.borrow_mut();
+    let contact_impulse_thresholds = contact_listener.contact_impulse_thresholds_mut();
+    for entity in removed.read() {
+        contact_impulse_thresholds.remove(&entity);
+    }
+    for (entity, threshold) in thresholds.iter() {
+        contact_impulse_thresholds.insert(entity, threshold.0);
+    }
+}
+
+fn sync_one_way_platforms(
+    b2_world: NonSend<b2World>,
+    platforms: Query<(Entity, &OneWayPlatform), Changed<OneWayPlatform>>,
+    mut removed: RemovedComponents<OneWayPlatform>,
+) {
+    let contact_listener = b2_world.contact_listener();
+    let mut contact_listener = contact_listener.borrow_mut();
+    let one_way_platforms = contact_listener.one_way_platforms_mut();
+    for entity in removed.read() {
+        one_way_platforms.remove(&entity);
+    }
+    for (entity, platform) in platforms.iter() {
+        one_way_platforms.insert(entity, *platform);
+    }
+}
+
+fn sync_bodies_with_contacts(
+    b2_world: NonSend<b2World>,
+    added: Query<Entity, Added<b2Contacts>>,
+    mut removed: RemovedComponents<b2Contacts>,
+) {
+    let contact_listener = b2_world.contact_listener();
+    let mut contact_listener = contact_listener.borrow_mut();
+    let bodies_with_contacts = contact_listener.bodies_with_contacts_mut();
+    for entity in removed.read() {
+        bodies_with_contacts.remove(&entity);
+    }
+    for entity in added.iter() {
+        bodies_with_contacts.insert(entity);
+    }
+}
+
 fn apply_forces(
     mut b2_world: NonSendMut<b2World>,
     external_forces: Query<(Entity, &ExternalForce)>,
@@ -367,6 +837,142 @@ fn sync_bodies_from_world(b2_world: NonSend<b2World>, mut bodies: Query<(Entity,
     }
 }
 
+fn sync_joints_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut joints: Query<(Entity, &mut b2Joint)>,
+) {
+    let inv_dt = 1. / settings.time_step;
+    for (entity, mut joint) in joints.iter_mut() {
+        if let Some(joint_ptr) = b2_world.get_joint_ptr(&entity) {
+            joint
+                .bypass_change_detection()
+                .sync_with_world(joint_ptr, inv_dt);
+        }
+    }
+}
+
+fn sync_revolute_joints_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut joints: Query<(Entity, &mut b2RevoluteJoint)>,
+) {
+    let inv_dt = 1. / settings.time_step;
+    for (entity, mut joint) in joints.iter_mut() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity);
+        if let Some(JointPtr::Revolute(joint_ptr)) = joint_ptr {
+            // bypass change detection so that reading back the joint state does not
+            // trigger sync_revolute_joints_to_world on the next frame
+            joint
+                .bypass_change_detection()
+                .sync_with_world(joint_ptr.as_mut(), inv_dt);
+        }
+    }
+}
+
+fn sync_prismatic_joints_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut joints: Query<(Entity, &mut b2PrismaticJoint)>,
+) {
+    let inv_dt = 1. / settings.time_step;
+    for (entity, mut joint) in joints.iter_mut() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity);
+        if let Some(JointPtr::Prismatic(joint_ptr)) = joint_ptr {
+            joint
+                .bypass_change_detection()
+                .sync_with_world(joint_ptr.as_mut(), inv_dt);
+        }
+    }
+}
+
+fn sync_distance_joints_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    mut joints: Query<(Entity, &mut b2DistanceJoint)>,
+) {
+    for (entity, mut joint) in joints.iter_mut() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity);
+        if let Some(JointPtr::Distance(joint_ptr)) = joint_ptr {
+            joint
+                .bypass_change_detection()
+                .sync_with_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn sync_wheel_joints_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    settings: Res<b2WorldSettings>,
+    mut joints: Query<(Entity, &mut b2WheelJoint)>,
+) {
+    let inv_dt = 1. / settings.time_step;
+    for (entity, mut joint) in joints.iter_mut() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity);
+        if let Some(JointPtr::Wheel(joint_ptr)) = joint_ptr {
+            joint
+                .bypass_change_detection()
+                .sync_with_world(joint_ptr.as_mut(), inv_dt);
+        }
+    }
+}
+
+fn sync_pulley_joints_from_world(
+    mut b2_world: NonSendMut<b2World>,
+    mut joints: Query<(Entity, &mut b2PulleyJoint)>,
+) {
+    for (entity, mut joint) in joints.iter_mut() {
+        let joint_ptr = b2_world.get_joint_ptr(&entity);
+        if let Some(JointPtr::Pulley(joint_ptr)) = joint_ptr {
+            joint
+                .bypass_change_detection()
+                .sync_with_world(joint_ptr.as_mut());
+        }
+    }
+}
+
+fn break_joints(
+    mut b2_world: NonSendMut<b2World>,
+    breakables: Query<(Entity, &b2Joint, &b2Breakable)>,
+    joints: Query<&b2Joint>,
+    mut bodies: Query<&mut b2Body>,
+    mut joint_broken_events: EventWriter<b2JointBrokenEvent>,
+    mut commands: Commands,
+) {
+    let mut destroyed_joint_entities = HashSet::new();
+    for (entity, joint, breakable) in breakables.iter() {
+        // the joint might already be gone as a gear joint of a joint that broke earlier
+        if destroyed_joint_entities.contains(&entity) {
+            continue;
+        }
+        if !breakable.is_exceeded_by(joint.reaction_force(), joint.reaction_torque()) {
+            continue;
+        }
+
+        // gear joints referring to the broken joint are destroyed along with it, so they are
+        // reported as broken as well
+        let destroyed_joints = b2_world.destroy_joint_for_entity(entity);
+        for destroyed_joint in destroyed_joints {
+            forget_destroyed_joint(&destroyed_joint, &mut bodies);
+            if let Some(joint_commands) = commands.get_entity(destroyed_joint.joint) {
+                joint_commands.despawn_recursive();
+            }
+
+            destroyed_joint_entities.insert(destroyed_joint.joint);
+
+            if let Ok(destroyed_joint_component) = joints.get(destroyed_joint.joint) {
+                let [body_a, body_b] = destroyed_joint.bodies;
+                joint_broken_events.send(b2JointBrokenEvent {
+                    joint: destroyed_joint.joint,
+                    body_a,
+                    body_b,
+                    reaction_force: destroyed_joint_component.reaction_force(),
+                    reaction_torque: destroyed_joint_component.reaction_torque(),
+                });
+            }
+        }
+    }
+}
+
 fn sync_particle_systems_from_world(
     b2_world: NonSend<b2World>,
     mut particle_systems: Query<(Entity, &mut b2ParticleSystem)>,
@@ -376,9 +982,114 @@ fn sync_particle_systems_from_world(
     }
 }
 
+fn update_sensor_overlaps(
+    b2_world: NonSend<b2World>,
+    mut sensors: Query<(Entity, &mut b2SensorOverlaps)>,
+    mut sensor_enter_events: EventWriter<b2SensorEnterEvent>,
+    mut sensor_exit_events: EventWriter<b2SensorExitEvent>,
+) {
+    let mut current_overlaps: HashMap<Entity, HashMap<Entity, Entity>> = HashMap::new();
+    {
+        let contact_listener = b2_world.contact_listener();
+        let contact_listener = contact_listener.borrow();
+        for contact in contact_listener.fixture_contacts().values() {
+            if sensors.contains(contact.fixture_a) {
+                current_overlaps
+                    .entry(contact.fixture_a)
+                    .or_default()
+                    .insert(contact.fixture_b, contact.body_b);
+            }
+            if sensors.contains(contact.fixture_b) {
+                current_overlaps
+                    .entry(contact.fixture_b)
+                    .or_default()
+                    .insert(contact.fixture_a, contact.body_a);
+            }
+        }
+    }
+
+    for (sensor, mut sensor_overlaps) in sensors.iter_mut() {
+        let overlaps = current_overlaps.remove(&sensor).unwrap_or_default();
+        if overlaps == *sensor_overlaps.overlaps() {
+            continue;
+        }
+
+        for (fixture, body) in overlaps.iter() {
+            if !sensor_overlaps.contains_fixture(*fixture) {
+                sensor_enter_events.send(b2SensorEnterEvent {
+                    sensor,
+                    fixture: *fixture,
+                    body: *body,
+                });
+            }
+        }
+        for (fixture, body) in sensor_overlaps.overlaps().iter() {
+            if !overlaps.contains_key(fixture) {
+                sensor_exit_events.send(b2SensorExitEvent {
+                    sensor,
+                    fixture: *fixture,
+                    body: *body,
+                });
+            }
+        }
+
+        *sensor_overlaps.overlaps_mut() = overlaps;
+    }
+}
+
+fn update_body_contacts(
+    b2_world: NonSend<b2World>,
+    fixtures: Query<&b2Fixture>,
+    mut bodies: Query<&mut b2Contacts>,
+) {
+    for mut body_contacts in bodies.iter_mut() {
+        body_contacts.contacts_mut().clear();
+    }
+
+    let is_sensor = |fixture: Entity| {
+        fixtures
+            .get(fixture)
+            .map_or(false, |fixture| fixture.def().is_sensor)
+    };
+
+    let contact_listener = b2_world.contact_listener();
+    let contact_listener = contact_listener.borrow();
+    for contact in contact_listener.fixture_contacts().values() {
+        if is_sensor(contact.fixture_a) || is_sensor(contact.fixture_b) {
+            continue;
+        }
+
+        if let Ok(mut body_contacts) = bodies.get_mut(contact.body_a) {
+            body_contacts.contacts_mut().push(b2BodyContact {
+                fixture: contact.fixture_a,
+                other_fixture: contact.fixture_b,
+                other_body: contact.body_b,
+                points: contact.points,
+                point_count: contact.point_count,
+                normal: contact.normal,
+            });
+        }
+        if let Ok(mut body_contacts) = bodies.get_mut(contact.body_b) {
+            body_contacts.contacts_mut().push(b2BodyContact {
+                fixture: contact.fixture_b,
+                other_fixture: contact.fixture_a,
+                other_body: contact.body_a,
+                points: contact.points,
+                point_count: contact.point_count,
+                normal: -contact.normal,
+            });
+        }
+    }
+}
+
 fn send_contact_events(
     mut begin_contact_events: EventWriter<b2BeginContactEvent>,
     mut end_contact_events: EventWriter<b2EndContactEvent>,
+    mut contact_impulse_events: EventWriter<b2ContactImpulseEvent>,
+    mut particle_body_contact_begin_events: EventWriter<b2ParticleBodyContactBegin>,
+    mut particle_body_contact_end_events: EventWriter<b2ParticleBodyContactEnd>,
+    mut particle_particle_contact_begin_events: EventWriter<b2ParticleParticleContactBegin>,
+    mut particle_particle_contact_end_events: EventWriter<b2ParticleParticleContactEnd>,
     b2_world: NonSendMut<b2World>,
 ) {
     let contact_listener = b2_world.contact_listener();
@@ -398,6 +1109,35 @@ fn send_contact_events(
         for contact in ended_contacts.values() {
             end_contact_events.send(b2EndContactEvent(contact.clone()))
         }
+
+        for impulse in contact_listener.contact_impulses().values() {
+            contact_impulse_events.send(b2ContactImpulseEvent(impulse.clone()));
+        }
+
+        particle_body_contact_begin_events.send_batch(
+            contact_listener
+                .begun_particle_body_contacts()
+                .iter()
+                .copied(),
+        );
+        particle_body_contact_end_events.send_batch(
+            contact_listener
+                .ended_particle_body_contacts()
+                .iter()
+                .copied(),
+        );
+        particle_particle_contact_begin_events.send_batch(
+            contact_listener
+                .begun_particle_particle_contacts()
+                .iter()
+                .copied(),
+        );
+        particle_particle_contact_end_events.send_batch(
+            contact_listener
+                .ended_particle_particle_contacts()
+                .iter()
+                .copied(),
+        );
     }
 
     contact_listener.clear_contact_changes();
@@ -424,6 +1164,21 @@ fn copy_particle_system_contacts(
                 .iter()
                 .map(|c| b2ParticleBodyContact::from_ffi_contact(c)),
         );
+
+        let particle_contacts = unsafe {
+            let particle_contacts = particle_system_ptr.as_ref().GetContacts();
+            let count =
+                i32::from(int32::from(particle_system_ptr.as_ref().GetContactCount())) as usize;
+            std::slice::from_raw_parts(particle_contacts, count)
+        };
+
+        let new_particle_contacts = particle_system_contacts.particle_contacts_mut();
+        new_particle_contacts.clear();
+        new_particle_contacts.extend(
+            particle_contacts
+                .iter()
+                .map(|c| b2ParticleContact::from_ffi_contact(c)),
+        );
     }
 }
 
@@ -471,6 +1226,9 @@ impl Plugin for LiquidFunDebugDrawPlugin {
                     .after(TransformSystem::TransformPropagate)
                     .after(destroy_removed_bodies),
                 draw_particle_systems.after(TransformSystem::TransformPropagate),
+                draw_joints
+                    .after(TransformSystem::TransformPropagate)
+                    .after(destroy_removed_bodies),
             ),
         );
     }
@@ -550,3 +1308,201 @@ fn draw_particle_systems(
         });
     }
 }
+
+fn draw_joints(
+    joints: Query<(
+        &b2Joint,
+        &DebugDrawJoint,
+        Option<&b2RevoluteJoint>,
+        Option<&b2PrismaticJoint>,
+    )>,
+    bodies: Query<&b2Body>,
+    mut gizmos: Gizmos,
+) {
+    for (joint, debug_draw_joint, revolute_joint, prismatic_joint) in joints.iter() {
+        let body_a = bodies.get(*joint.body_a());
+        let body_b = bodies.get(*joint.body_b());
+        if body_a.is_err() || body_b.is_err() {
+            continue;
+        }
+        let (body_a, body_b) = (body_a.unwrap(), body_b.unwrap());
+
+        let color = debug_draw_joint.color;
+        let anchor_a = joint.anchor_a();
+        let anchor_b = joint.anchor_b();
+        gizmos.line_2d(body_a.position, anchor_a, color);
+        gizmos.line_2d(anchor_a, anchor_b, color);
+        gizmos.line_2d(body_b.position, anchor_b, color);
+        gizmos.circle_2d(anchor_a, debug_draw_joint.anchor_scale, color);
+        gizmos.circle_2d(anchor_b, debug_draw_joint.anchor_scale, color);
+
+        if let Some(revolute_joint) = revolute_joint {
+            let radius = debug_draw_joint.limit_scale;
+            let reference_angle = body_a.angle + revolute_joint.reference_angle();
+            let current_angle = reference_angle + revolute_joint.joint_angle();
+            gizmos.line_2d(
+                anchor_b,
+                anchor_b + Vec2::from_angle(current_angle) * radius,
+                color,
+            );
+
+            if revolute_joint.enable_limit {
+                let lower_angle = reference_angle + revolute_joint.lower_angle;
+                let upper_angle = reference_angle + revolute_joint.upper_angle;
+                let segments = 16;
+                gizmos.linestrip_2d(
+                    (0..=segments).map(|i| {
+                        let t = i as f32 / segments as f32;
+                        let angle = lower_angle + (upper_angle - lower_angle) * t;
+                        anchor_b + Vec2::from_angle(angle) * radius
+                    }),
+                    color,
+                );
+                gizmos.line_2d(
+                    anchor_b,
+                    anchor_b + Vec2::from_angle(lower_angle) * radius,
+                    debug_draw_joint.lower_limit_color,
+                );
+                gizmos.line_2d(
+                    anchor_b,
+                    anchor_b + Vec2::from_angle(upper_angle) * radius,
+                    debug_draw_joint.upper_limit_color,
+                );
+            }
+        }
+
+        if let Some(prismatic_joint) = prismatic_joint {
+            let axis = Vec2::from_angle(body_a.angle).rotate(prismatic_joint.local_axis_a());
+            if prismatic_joint.enable_limit {
+                let perp = axis.perp() * debug_draw_joint.limit_scale;
+                let lower = anchor_a + axis * prismatic_joint.lower_translation;
+                let upper = anchor_a + axis * prismatic_joint.upper_translation;
+                gizmos.line_2d(lower, upper, color);
+                gizmos.line_2d(
+                    lower - perp,
+                    lower + perp,
+                    debug_draw_joint.lower_limit_color,
+                );
+                gizmos.line_2d(
+                    upper - perp,
+                    upper + perp,
+                    debug_draw_joint.upper_limit_color,
+                );
+            } else {
+                gizmos.line_2d(anchor_a - axis, anchor_a + axis, color);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LiquidFunMousePickingPlugin {
+    settings: MousePickingSettings,
+}
+
+impl LiquidFunMousePickingPlugin {
+    pub fn new(settings: MousePickingSettings) -> LiquidFunMousePickingPlugin {
+        LiquidFunMousePickingPlugin { settings }
+    }
+}
+
+impl Plugin for LiquidFunMousePickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .init_resource::<MousePickingState>()
+            .add_systems(Update, update_mouse_picking);
+    }
+}
+
+fn update_mouse_picking(
+    mut commands: Commands,
+    settings: Res<MousePickingSettings>,
+    mut state: ResMut<MousePickingState>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    b2_world: NonSend<b2World>,
+    mut bodies: Query<&mut b2Body>,
+    mut mouse_joints: Query<&mut b2MouseJoint>,
+) {
+    let cursor_position = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .and_then(|cursor_position| {
+            cameras
+                .iter()
+                .find(|(camera, _)| camera.is_active)
+                .and_then(|(camera, transform)| cursor_to_world(camera, transform, cursor_position))
+        });
+
+    let action = mouse_picking_action(
+        buttons.pressed(settings.button),
+        buttons.just_pressed(settings.button),
+        state.joint.is_some(),
+    );
+
+    match action {
+        MousePickingAction::Grab => {
+            if let None = cursor_position {
+                return;
+            }
+            let cursor_position = cursor_position.unwrap();
+
+            let picked_body = find_pickable_body(&b2_world, cursor_position);
+            if let None = picked_body {
+                return;
+            }
+            let picked_body = picked_body.unwrap();
+
+            let anchor_body = *state
+                .anchor_body
+                .get_or_insert_with(|| commands.spawn(b2BodyBundle::default()).id());
+
+            let mut body = bodies.get_mut(picked_body).unwrap();
+            body.awake = true;
+            let (stiffness, damping) = linear_stiffness(
+                settings.frequency_hertz,
+                settings.damping_ratio,
+                0.,
+                body.mass(),
+            );
+            let joint_def = b2MouseJointDef {
+                target: cursor_position,
+                max_force: settings.max_force_per_mass * body.mass(),
+                stiffness,
+                damping,
+            };
+            let joint_entity = commands
+                .spawn_empty()
+                .add(CreateMouseJoint::new(
+                    anchor_body,
+                    picked_body,
+                    false,
+                    &joint_def,
+                ))
+                .id();
+            state.joint = Some(joint_entity);
+            state.picked_body = Some(picked_body);
+        }
+        MousePickingAction::Drag => {
+            let joint = state
+                .joint
+                .and_then(|joint_entity| mouse_joints.get_mut(joint_entity).ok());
+            if let (Some(cursor_position), Some(mut joint)) = (cursor_position, joint) {
+                if joint.target != cursor_position {
+                    joint.target = cursor_position;
+                }
+            }
+        }
+        MousePickingAction::Release => {
+            if let Some(joint_entity) = state.joint.take() {
+                if let Some(mut joint_commands) = commands.get_entity(joint_entity) {
+                    joint_commands.despawn();
+                }
+            }
+            state.picked_body = None;
+        }
+        MousePickingAction::None => {}
+    }
+}
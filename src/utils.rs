@@ -50,3 +50,25 @@ impl DebugDrawFixtures {
 
 #[derive(Component, Debug)]
 pub struct DebugDrawParticleSystem {}
+
+#[derive(Component, Clone, Debug)]
+pub struct DebugDrawJoint {
+    pub color: Color,
+    pub lower_limit_color: Color,
+    pub upper_limit_color: Color,
+
+    pub anchor_scale: f32,
+    pub limit_scale: f32,
+}
+
+impl Default for DebugDrawJoint {
+    fn default() -> Self {
+        Self {
+            color: Color::rgb(0.5, 0.8, 0.8),
+            lower_limit_color: Color::GREEN,
+            upper_limit_color: Color::RED,
+            anchor_scale: 0.05,
+            limit_scale: 0.5,
+        }
+    }
+}